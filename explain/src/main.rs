@@ -0,0 +1,96 @@
+use argparse::{ArgumentParser, Store};
+use game_sdk::{gamerules, Board, GameState};
+use logic_player::{
+    explain_mate, ClopEvaluator, Evaluator, Network, NetworkEvaluator, Term, TexelEvaluator,
+    DEFAULT_TEXEL,
+};
+
+fn print_terms(terms: &[Term]) {
+    println!(
+        "{:<22} | {:<5} | {:>10} | {:>12}",
+        "term", "color", "raw", "contribution"
+    );
+    println!("{}", "-".repeat(58));
+    for term in terms {
+        let color = match term.color {
+            Some(color) => format!("{}", color),
+            None => "-".to_string(),
+        };
+        println!(
+            "{:<22} | {:<5} | {:>10.3} | {:>12.3}",
+            term.name, color, term.raw, term.contribution
+        );
+    }
+}
+
+fn load_state(file: &str, index: usize) -> GameState {
+    if file.is_empty() {
+        return gamerules::get_random_state();
+    }
+    let states = logger::read_file(file).expect("Unable to read logged positions");
+    let state = states.get(index).unwrap_or_else(|| {
+        panic!("{} only holds {} positions", file, states.len());
+    });
+    return GameState::new(Board::new(state.board), state.turn);
+}
+
+fn main() {
+    let mut evaluator_name = "clop".to_string();
+    let mut network = String::new();
+    let mut file = String::new();
+    let mut index: usize = 0;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.refer(&mut evaluator_name).add_option(
+            &["-e", "--evaluator"],
+            Store,
+            "Evaluation to explain: clop, texel or network, defaults to clop",
+        );
+        ap.refer(&mut network).add_option(
+            &["-n", "--network"],
+            Store,
+            "Weights file of the network evaluator",
+        );
+        ap.refer(&mut file).add_option(
+            &["-f", "--file"],
+            Store,
+            "Logged file to take the position from, a random start position otherwise",
+        );
+        ap.refer(&mut index).add_option(
+            &["-i", "--index"],
+            Store,
+            "Index of the position within the logged file",
+        );
+        ap.parse_args_or_exit();
+    }
+
+    let evaluator: Box<dyn Evaluator> = match evaluator_name.as_str() {
+        "clop" => Box::new(ClopEvaluator),
+        "texel" => Box::new(TexelEvaluator::new(DEFAULT_TEXEL)),
+        "network" => Box::new(NetworkEvaluator::new(
+            Network::from_file(&network).expect("Unable to load network"),
+        )),
+        _ => panic!("Unknown evaluator {}", evaluator_name),
+    };
+    let state = load_state(&file, index);
+    println!("{:?}", state.board);
+    println!(
+        "turn {}, {} to move\n",
+        state.turn,
+        state.get_current_player_color()
+    );
+
+    let terms = evaluator.explain(&state);
+    print_terms(&terms);
+    let total: f32 = terms.iter().map(|term| term.contribution).sum();
+    println!(
+        "\ntotal {:.3}, evaluate {:.3}",
+        total,
+        evaluator.evaluate(&state)
+    );
+
+    if gamerules::is_finished(&state) {
+        println!("\nThe game is over, searches rate it with");
+        print_terms(&explain_mate(&state));
+    }
+}
@@ -1,6 +1,7 @@
 use board::Board;
 use gamestate::GameState;
-use rand::{thread_rng, Rng};
+use rand::rngs::StdRng;
+use rand::{thread_rng, Rng, SeedableRng};
 use states::FieldType;
 use states::Move;
 use states::PlayerColor;
@@ -104,6 +105,16 @@ pub fn is_finished(state: &GameState) -> bool {
 struct Point(i8, i8);
 
 pub fn get_random_state() -> GameState {
+    return get_random_state_with(&mut thread_rng());
+}
+
+/// The start position with obstacles drawn from a generator seeded with `seed`, the same seed
+/// always gives the same position
+pub fn get_seeded_state(seed: u64) -> GameState {
+    return get_random_state_with(&mut StdRng::seed_from_u64(seed));
+}
+
+fn get_random_state_with<R: Rng>(rng: &mut R) -> GameState {
     let mut fields = [[FieldType::Free; 10]; 10];
     for i in 1..9 {
         fields[0][i] = FieldType::RedPlayer;
@@ -117,7 +128,6 @@ pub fn get_random_state() -> GameState {
             blockable_fields.push(Point(i, j));
         }
     }
-    let mut rng = thread_rng();
     let obs = blockable_fields
         .get(rng.gen_range(0, blockable_fields.len()) as usize)
         .unwrap();
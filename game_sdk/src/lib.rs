@@ -44,6 +44,14 @@ pub trait ClientListener {
 	/// Is called with a room number, not really interesting for a client implementation
 	fn on_join(&mut self, _room: &Room) {}
 
-	/// Is called when the TCPListener is idling. Should do some work but take no longer than 1ms
+	/// Whether the listener wants `on_idle` calls while the client waits for the server.
+	/// Without any pondering listener the client just blocks on the connection
+	fn ponders(&self) -> bool {
+		return false;
+	}
+
+	/// Is called when the TCPListener is idling. Should do some work but take no longer than 1ms.
+	/// There is no background thread: the client calls this over and over on its own thread while
+	/// a listener `ponders`, and reads the next message of the server only once it returned
 	fn on_idle(&mut self) {}
 }
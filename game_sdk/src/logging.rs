@@ -2,10 +2,12 @@ use crate::Move;
 use crate::GameState;
 use crate::gamerules;
 use crate::PlayerColor;
+use std::sync::mpsc::Sender;
 
 pub enum Data {
     Step(State),
     End(EndState),
+    Report(SearchReport),
 }
 
 pub struct EndState {
@@ -101,4 +103,60 @@ impl MoveValuePair {
         );
         return string_version;
     }
-}
\ No newline at end of file
+}
+
+/// Longest principal variation a report carries
+pub const MAX_PV_LENGTH: usize = 30;
+
+/// Statistics of one root move of a search, see `SearchReport`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct RootMove {
+    pub action: Move,
+    /// samples of the move, `None` for the alpha-beta searches
+    pub visits: Option<f32>,
+    /// value of the move for the side to move, the win chance for MCTS and the evaluation
+    /// for alpha-beta, where moves that failed low only have a bound
+    pub score: f32,
+    /// the expected continuation, starting with `action`
+    pub pv: Vec<Move>,
+}
+
+/// What an engine found for one move request, sent through `send_report`
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct SearchReport {
+    pub engine: String,
+    pub id: i64,
+    pub turn: u8,
+    pub time_ms: i64,
+    pub action: Option<Move>,
+    pub score: f32,
+    /// deepest fully searched iteration, `None` for MCTS
+    pub depth: Option<u8>,
+    pub nodes: u64,
+    /// the proven outcome if the search solved the position, e.g. `Win(3)`
+    pub proven: Option<String>,
+    pub pv: Vec<Move>,
+    pub moves: Vec<RootMove>,
+    /// engine specific numbers like table hit rates
+    pub stats: Vec<(String, f32)>,
+}
+
+impl SearchReport {
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string(self).expect("Unable to serialize report");
+    }
+
+    pub fn from_json(json: &str) -> Result<SearchReport, String> {
+        return serde_json::from_str(json).map_err(|e| e.to_string());
+    }
+}
+
+/// The one way engines publish their reports: to the logging thread if there is one,
+/// otherwise as a line of JSON on stdout for the debug ids below 0
+pub fn send_report(tx: &Option<Sender<Data>>, report: SearchReport) {
+    if let Some(ref tx) = tx {
+        tx.send(Data::Report(report)).unwrap();
+    } else if report.id < 0 {
+        println!("{}", report.to_json());
+    }
+}
@@ -6,6 +6,9 @@ extern crate serde;
 extern crate serde_json;
 
 mod logger;
+mod reader;
 mod states;
 
 pub use logger::Logger;
+pub use reader::{read_directory, read_file};
+pub use states::WriteState;
@@ -1,9 +1,12 @@
 use std::collections::HashMap;
 use std::fs;
+use std::fs::OpenOptions;
+use std::io::{BufWriter, Write};
 use std::path::Path;
 
 use serde_json;
 use game_sdk::logging::EndState;
+use game_sdk::logging::SearchReport;
 use game_sdk::logging::State;
 use states::WriteState;
 
@@ -14,6 +17,7 @@ pub struct Logger {
     index: u32,
     out_queue: Vec<WriteState>,
     state_map: HashMap<u32, Vec<State>>,
+    reports: Option<BufWriter<fs::File>>,
 }
 
 impl Logger {
@@ -28,9 +32,26 @@ impl Logger {
             index,
             out_queue: Vec::new(),
             state_map: HashMap::new(),
+            reports: None,
         };
     }
 
+    /// Appends every report from now on to `path` as one line of JSON each
+    pub fn set_report_file(&mut self, path: &str) {
+        let file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .expect("Unable to open report file");
+        self.reports = Some(BufWriter::new(file));
+    }
+
+    pub fn add_report(&mut self, report: &SearchReport) {
+        if let Some(ref mut reports) = self.reports {
+            writeln!(reports, "{}", report.to_json()).expect("Unable to write report");
+        }
+    }
+
     pub fn add_to_queue(&mut self, state: WriteState) {
         self.out_queue.push(state);
 
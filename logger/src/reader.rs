@@ -0,0 +1,27 @@
+use std::fs;
+
+use serde_json;
+use states::WriteState;
+
+/// Reads every file `Logger` wrote into `directory`, files that fail to parse are skipped
+pub fn read_directory(directory: &str) -> Vec<WriteState> {
+    let mut states = Vec::new();
+    let entries = fs::read_dir(directory).expect("Unable to read directory");
+    for entry in entries {
+        let path = entry.expect("Unable to read directory entry").path();
+        if !path.is_file() {
+            continue;
+        }
+        match read_file(&path.to_string_lossy()) {
+            Ok(file_states) => states.extend(file_states),
+            Err(e) => println!("Skipping {}: {}", path.display(), e),
+        }
+    }
+    return states;
+}
+
+/// Reads the positions of a single file `Logger` wrote
+pub fn read_file(path: &str) -> Result<Vec<WriteState>, String> {
+    let content = fs::read_to_string(path).map_err(|e| e.to_string())?;
+    return serde_json::from_str(&content).map_err(|e| e.to_string());
+}
@@ -5,12 +5,12 @@ use game_sdk::PlayerColor;
 
 #[derive(Serialize, Deserialize)]
 pub struct WriteState {
-    winner: Option<PlayerColor>,
-    board: [[FieldType; 10]; 10],
-    moves: Vec<MoveValuePair>,
-    current_color: PlayerColor,
-    turn: u8,
-    data: Vec<(String, f32)>,
+    pub winner: Option<PlayerColor>,
+    pub board: [[FieldType; 10]; 10],
+    pub moves: Vec<MoveValuePair>,
+    pub current_color: PlayerColor,
+    pub turn: u8,
+    pub data: Vec<(String, f32)>,
 }
 
 impl WriteState {
@@ -0,0 +1,225 @@
+use game_sdk::{GameState, Move, PlayerColor};
+use hashbrown::HashMap;
+use rand::Rng;
+
+use crate::search::transposition::{mix, MinimalState};
+
+use std::fs;
+
+/// Statistics of one move played in a book position
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BookMove {
+    /// index of the origin field, `x + y * 10`
+    pub from: u8,
+    pub to: u8,
+    pub games: u32,
+    /// sum of the results for the side that moved, 1 for a win and 0.5 for a draw
+    pub score: f32,
+}
+
+impl BookMove {
+    fn matches(&self, action: &Move) -> bool {
+        return self.from == action.x + action.y * 10
+            && self.to == action.dest_x + action.dest_y * 10;
+    }
+
+    fn mean(&self) -> f32 {
+        return self.score / self.games as f32;
+    }
+}
+
+/// Hash of the fishes, the turn and the obstacles of `state`
+fn position_key(state: &GameState) -> u64 {
+    let obstacles = state.board.obstacle_fields.bits;
+    return mix(MinimalState::from_state(state).key()
+        ^ obstacles as u64
+        ^ (obstacles >> 64) as u64);
+}
+
+/// Moves played in the first turns of finished games, keyed by the hash of the position.
+///
+/// The text format has one line per move: the position key in hex, origin and destination
+/// index, the number of games and the summed score. Lines starting with `#` are ignored.
+#[derive(Clone, Debug)]
+pub struct OpeningBook {
+    entries: HashMap<u64, Vec<BookMove>>,
+    /// moves played in fewer games are never chosen
+    pub min_games: u32,
+}
+
+impl OpeningBook {
+    pub fn new() -> OpeningBook {
+        return OpeningBook {
+            entries: HashMap::new(),
+            min_games: 2,
+        };
+    }
+
+    pub fn from_file(path: &str) -> Result<OpeningBook, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        return OpeningBook::parse(&content);
+    }
+
+    pub fn parse(content: &str) -> Result<OpeningBook, String> {
+        let mut book = OpeningBook::new();
+        for line in content.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() || words[0].starts_with('#') {
+                continue;
+            }
+            if words.len() != 5 {
+                return Err(format!("Expected 5 values in {}", line));
+            }
+            let key =
+                u64::from_str_radix(words[0], 16).map_err(|_| format!("Bad key {}", words[0]))?;
+            let number_error = |word: &str| format!("{} is no number", word);
+            let entry = BookMove {
+                from: words[1].parse().map_err(|_| number_error(words[1]))?,
+                to: words[2].parse().map_err(|_| number_error(words[2]))?,
+                games: words[3].parse().map_err(|_| number_error(words[3]))?,
+                score: words[4].parse().map_err(|_| number_error(words[4]))?,
+            };
+            book.entries.entry(key).or_insert_with(Vec::new).push(entry);
+        }
+        return Ok(book);
+    }
+
+    /// Writes the book in the format `parse` reads
+    pub fn to_text(&self) -> String {
+        let mut keys: Vec<&u64> = self.entries.keys().collect();
+        keys.sort();
+        let mut text = String::new();
+        for key in keys {
+            for entry in self.entries[key].iter() {
+                text += &format!(
+                    "{:016x} {} {} {} {}\n",
+                    key, entry.from, entry.to, entry.games, entry.score
+                );
+            }
+        }
+        return text;
+    }
+
+    /// Number of positions in the book
+    pub fn len(&self) -> usize {
+        return self.entries.len();
+    }
+
+    pub fn is_empty(&self) -> bool {
+        return self.entries.is_empty();
+    }
+
+    /// Adds the moves of a game played from `start` until `max_turn`, `winner` being its result
+    pub fn add_game(
+        &mut self,
+        start: &GameState,
+        moves: &[Move],
+        winner: Option<PlayerColor>,
+        max_turn: u8,
+    ) {
+        let mut state = start.clone();
+        for action in moves {
+            if state.turn >= max_turn {
+                break;
+            }
+            let color = state.get_current_player_color();
+            let result = match winner {
+                Some(winner) if winner == color => 1.,
+                Some(_) => 0.,
+                None => 0.5,
+            };
+            let key = position_key(&state);
+            let entries = self.entries.entry(key).or_insert_with(Vec::new);
+            match entries.iter_mut().find(|entry| entry.matches(action)) {
+                Some(entry) => {
+                    entry.games += 1;
+                    entry.score += result;
+                }
+                None => entries.push(BookMove {
+                    from: action.x + action.y * 10,
+                    to: action.dest_x + action.dest_y * 10,
+                    games: 1,
+                    score: result,
+                }),
+            }
+            state.perform(action, &color);
+        }
+    }
+
+    /// Book move for `state`, `None` if the position is unknown.
+    ///
+    /// With a `randomness` of 0 the move with the best mean result is played. Otherwise moves
+    /// are drawn from `rng` with a softmax over their mean results, `randomness` being the
+    /// temperature.
+    pub fn lookup<R: Rng>(&self, state: &GameState, randomness: f32, rng: &mut R) -> Option<Move> {
+        let entries = self.entries.get(&position_key(state))?;
+        let candidates: Vec<(Move, f32)> = state
+            .get_move_list()
+            .into_iter()
+            .filter_map(|action| {
+                entries
+                    .iter()
+                    .find(|entry| entry.matches(&action) && entry.games >= self.min_games)
+                    .map(|entry| (action, entry.mean()))
+            })
+            .collect();
+        if candidates.is_empty() {
+            return None;
+        }
+        let best = candidates
+            .iter()
+            .map(|&(_, mean)| mean)
+            .fold(f32::MIN, f32::max);
+        if randomness <= 0. {
+            return candidates
+                .iter()
+                .find(|&&(_, mean)| mean == best)
+                .map(|&(action, _)| action);
+        }
+        let weights: Vec<f32> = candidates
+            .iter()
+            .map(|&(_, mean)| ((mean - best) / randomness).exp())
+            .collect();
+        let mut pick = rng.gen::<f32>() * weights.iter().sum::<f32>();
+        for (&(action, _), weight) in candidates.iter().zip(weights.iter()) {
+            if pick < *weight {
+                return Some(action);
+            }
+            pick -= weight;
+        }
+        return candidates.last().map(|&(action, _)| action);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limit::engine_rng;
+    use game_sdk::gamerules;
+
+    #[test]
+    fn learns_and_round_trips() {
+        let start = gamerules::get_seeded_state(1);
+        let mut state = start.clone();
+        let mut moves = Vec::new();
+        for _ in 0..4 {
+            let action = state.get_move_list()[0];
+            let color = state.get_current_player_color();
+            state.perform(&action, &color);
+            moves.push(action);
+        }
+        let mut book = OpeningBook::new();
+        let mut rng = engine_rng(Some(1));
+        book.add_game(&start, &moves, Some(PlayerColor::Red), 2);
+        assert_eq!(book.len(), 2);
+        assert_eq!(book.lookup(&start, 0., &mut rng), None);
+
+        book.add_game(&start, &moves, None, 2);
+        assert_eq!(book.lookup(&start, 0., &mut rng), Some(moves[0]));
+        assert_eq!(book.lookup(&start, 1., &mut rng), Some(moves[0]));
+
+        let parsed = OpeningBook::parse(&book.to_text()).expect("Could not parse book");
+        assert_eq!(parsed.to_text(), book.to_text());
+        assert!(OpeningBook::parse("ff 1 2 3").is_err());
+    }
+}
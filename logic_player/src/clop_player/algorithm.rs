@@ -3,9 +3,13 @@ use game_sdk::GameState;
 use game_sdk::Move;
 use game_sdk::PlayerColor;
 
-use super::evaluation::texel_state as eval_state;
 use super::player::MinimaxParameters;
-use super::transposition::{EntryType, MinimalState, TranspositionTable};
+use crate::eval::Evaluator;
+use crate::limit::SearchClock;
+use crate::search::ordering::MoveOrdering;
+use crate::search::pruning::{is_capture, null_move, PruningStatistics};
+use crate::search::quiescence::Quiescence;
+use crate::search::transposition::{EntryType, MinimalState, TranspositionTable};
 
 use std::f32;
 
@@ -13,6 +17,9 @@ pub static MATE_SCORE: f32 = 200_000.0;
 
 pub struct SearchStatistics {
     pub nodes: usize,
+    /// nodes visited by the quiescence search, not included in `nodes`
+    pub q_nodes: usize,
+    pub pruning: PruningStatistics,
     pub re_searched: usize,
     pub probed: usize,
     pub aspire_probed: usize,
@@ -23,6 +30,8 @@ impl SearchStatistics {
     pub fn new() -> SearchStatistics {
         return SearchStatistics {
             nodes: 0,
+            q_nodes: 0,
+            pruning: PruningStatistics::default(),
             re_searched: 0,
             probed: 0,
             aspire_probed: 0,
@@ -31,6 +40,14 @@ impl SearchStatistics {
     }
 }
 
+fn rate_end(state: &GameState) -> f32 {
+    return match gamerules::get_winner(state) {
+        Some(PlayerColor::Red) => MATE_SCORE,
+        Some(PlayerColor::Blue) => -MATE_SCORE,
+        None => 0.,
+    };
+}
+
 pub fn minimax_rate(
     stats: &mut SearchStatistics,
     state: &GameState,
@@ -40,9 +57,10 @@ pub fn minimax_rate(
     beta: f32,
     depth: u8,
     tt: &mut TranspositionTable,
-    start_time: &time::Tm,
-    allotted_time: usize,
+    ordering: &mut MoveOrdering,
+    clock: &SearchClock,
     params: &MinimaxParameters,
+    evaluator: &dyn Evaluator,
 ) -> f32 {
     let mut state = state.clone();
     let color = state.get_current_player_color();
@@ -55,53 +73,14 @@ pub fn minimax_rate(
         beta,
         depth,
         tt,
-        start_time,
-        allotted_time,
+        ordering,
+        Some(*action),
+        clock,
         params,
+        evaluator,
     );
 }
 
-#[allow(dead_code)]
-pub fn q_search(
-    state: &GameState,
-    mut alpha: f32,
-    beta: f32,
-    player_index: i8,
-    params: &MinimaxParameters,
-) -> f32 {
-    let color = state.get_current_player_color();
-    alpha = f32::max(alpha, player_index as f32 * eval_state(state, params));
-    if alpha >= beta {
-        return beta;
-    }
-    for action in state.get_captures() {
-        let mut state = state.clone();
-        state.perform(&action, &color);
-        let rate;
-        if gamerules::is_finished(&state) {
-            if let Some(c) = gamerules::get_winner(&state) {
-                if c == PlayerColor::Red {
-                    // return MATE_SCORE * player_index as f32;
-                    rate = MATE_SCORE * player_index as f32;
-                } else {
-                    // return -MATE_SCORE * player_index as f32;
-                    rate = -MATE_SCORE * player_index as f32;
-                }
-            } else {
-                // return 0.;
-                rate = 0.;
-            }
-        } else {
-            rate = -q_search(&state, -beta, -alpha, -player_index, params);
-        }
-        alpha = f32::max(alpha, rate);
-        if alpha >= beta {
-            return beta;
-        }
-    }
-    return alpha;
-}
-
 pub fn minimax_rate_state(
     stats: &mut SearchStatistics,
     state: &GameState,
@@ -110,9 +89,11 @@ pub fn minimax_rate_state(
     mut beta: f32,
     depth: u8,
     tt: &mut TranspositionTable,
-    start_time: &time::Tm,
-    allotted_time: usize,
+    ordering: &mut MoveOrdering,
+    previous: Option<Move>,
+    clock: &SearchClock,
     params: &MinimaxParameters,
+    evaluator: &dyn Evaluator,
 ) -> f32 {
     stats.nodes += 1;
     let hash = MinimalState::from_state(&state);
@@ -133,28 +114,31 @@ pub fn minimax_rate_state(
         }
     }
     if gamerules::is_finished(state) {
-        if let Some(c) = gamerules::get_winner(state) {
-            if c == PlayerColor::Red {
-                return MATE_SCORE * player_index as f32;
-            } else {
-                return -MATE_SCORE * player_index as f32;
-            }
-        } else {
-            return 0.;
-        }
+        return rate_end(state) * player_index as f32;
     }
     if depth == 0 {
-        return player_index as f32 * eval_state(state, params);
+        if !params.search_q {
+            return player_index as f32 * evaluator.evaluate(state);
+        }
+        let mut quiescence = Quiescence::new(
+            &params.quiescence,
+            |state: &GameState| evaluator.evaluate(state),
+            rate_end,
+        );
+        let rate = quiescence.search(state, alpha, beta, player_index);
+        stats.q_nodes += quiescence.nodes;
+        return rate;
     }
     moves = state.get_move_list();
     move_len = moves.len();
     if move_len <= 0 {
         return -MATE_SCORE * player_index as f32;
     }
-    if (time::now() - *start_time).num_milliseconds() > allotted_time as i64 {
+    if depth > 2 && clock.expired(stats.nodes) {
         return f32::NAN;
     }
 
+    ordering.order(&mut moves, state, previous);
     if let Some(data) = tt.lookup(&hash) {
         let index = moves
             .iter()
@@ -164,62 +148,38 @@ pub fn minimax_rate_state(
         moves.insert(0, data.action);
     }
 
-    if params.search_q && depth == 1 {
-        let mut best_move = None;
-        let color = state.get_current_player_color();
-        for action in moves {
-            let mut state = state.clone();
-            state.perform(&action, &color);
-            let rate;
-            if gamerules::is_finished(&state) {
-                if let Some(c) = gamerules::get_winner(&state) {
-                    if c == PlayerColor::Red {
-                        rate = MATE_SCORE * player_index as f32;
-                    } else {
-                        rate = -MATE_SCORE * player_index as f32;
-                    }
-                } else {
-                    rate = 0.;
-                }
-            } else {
-                rate = -q_search(&state, -beta, -alpha, -player_index, params);
-            }
-            if rate > alpha {
-                alpha = rate;
-                best_move = Some(action);
-            } else if best_move == None {
-                best_move = Some(action);
-            }
-            if alpha >= beta {
-                break;
-            }
+    let null_window = beta - alpha < 1e-3;
+    let pruning = &params.pruning;
+    // only the null move and futility pruning look at the static evaluation
+    let static_eval = if pruning.null_move || pruning.futility {
+        player_index as f32 * evaluator.evaluate(state)
+    } else {
+        0.
+    };
+    if static_eval >= beta && pruning.allows_null_move(state, previous, depth, null_window) {
+        stats.pruning.null_tried += 1;
+        let rate = -minimax_rate_state(
+            stats,
+            &null_move(state),
+            -player_index,
+            -beta,
+            -beta + 1e-5,
+            depth - 1 - pruning.null_move_reduction,
+            tt,
+            ordering,
+            None,
+            clock,
+            params,
+            evaluator,
+        );
+        if rate.is_nan() {
+            return f32::NAN;
         }
-        if alpha <= start_alpha {
-            tt.insert(
-                &hash,
-                alpha,
-                depth,
-                &best_move.expect("can't happen"),
-                EntryType::UpperBound,
-            );
-        } else if alpha >= beta {
-            tt.insert(
-                &hash,
-                alpha,
-                depth,
-                &best_move.expect("can't happen"),
-                EntryType::LowerBound,
-            );
-        } else {
-            tt.insert(
-                &hash,
-                alpha,
-                depth,
-                &best_move.expect("can't happen"),
-                EntryType::Exact,
-            );
+        if rate >= beta {
+            stats.pruning.null_cutoffs += 1;
+            // a mate found after passing is no proof of anything
+            return f32::min(rate, MATE_SCORE - 1.);
         }
-        return alpha;
     }
 
     let mut drain = moves.drain(0..move_len);
@@ -233,15 +193,17 @@ pub fn minimax_rate_state(
         -alpha,
         depth - 1,
         tt,
-        start_time,
-        allotted_time,
+        ordering,
+        clock,
         params,
+        evaluator,
     );
-    if best == f32::NAN {
+    if best.is_nan() {
         return f32::NAN;
     }
     if best > alpha {
         if best >= beta {
+            ordering.cutoff(&best_move, state, previous, depth, 0);
             if best <= start_alpha {
                 tt.insert(&hash, best, depth, &best_move, EntryType::UpperBound);
             } else if best >= beta {
@@ -254,9 +216,16 @@ pub fn minimax_rate_state(
         alpha = best;
     }
     let color = state.get_current_player_color();
-    while let Some(action_considered) = drain.next() {
+    let mut cutoff_index = None;
+    for (index, action_considered) in drain.enumerate() {
+        let capture = is_capture(state, &action_considered);
+        if null_window && pruning.is_futile(static_eval, alpha, depth, capture) {
+            stats.pruning.futile += 1;
+            continue;
+        }
         let mut state = state.clone();
         state.perform(&action_considered, &color);
+        let reduced_depth = pruning.reduced_depth(depth, index + 1, capture);
         let mut rate;
         rate = -minimax_rate_state(
             stats,
@@ -264,13 +233,35 @@ pub fn minimax_rate_state(
             -player_index,
             -alpha - 1e-5,
             -alpha,
-            depth - 1,
+            reduced_depth,
             tt,
-            start_time,
-            allotted_time,
+            ordering,
+            Some(action_considered),
+            clock,
             params,
+            evaluator,
         );
-        if rate == f32::NAN {
+        if reduced_depth < depth - 1 {
+            stats.pruning.reduced += 1;
+            if rate > alpha {
+                stats.pruning.reduced_re_searched += 1;
+                rate = -minimax_rate_state(
+                    stats,
+                    &state,
+                    -player_index,
+                    -alpha - 1e-5,
+                    -alpha,
+                    depth - 1,
+                    tt,
+                    ordering,
+                    Some(action_considered),
+                    clock,
+                    params,
+                    evaluator,
+                );
+            }
+        }
+        if rate.is_nan() {
             return f32::NAN;
         }
         if alpha < rate && rate < beta {
@@ -282,11 +273,13 @@ pub fn minimax_rate_state(
                 -alpha,
                 depth - 1,
                 tt,
-                start_time,
-                allotted_time,
+                ordering,
+                Some(action_considered),
+                clock,
                 params,
+                evaluator,
             );
-            if rate == f32::NAN {
+            if rate.is_nan() {
                 return f32::NAN;
             }
             if rate > alpha {
@@ -297,10 +290,14 @@ pub fn minimax_rate_state(
             best = rate;
             best_move = action_considered;
             if rate >= beta {
+                cutoff_index = Some(index + 1);
                 break;
             }
         }
     }
+    if let Some(index) = cutoff_index {
+        ordering.cutoff(&best_move, state, previous, depth, index);
+    }
     if best <= start_alpha {
         tt.insert(&hash, best, depth, &best_move, EntryType::UpperBound);
     } else if best >= beta {
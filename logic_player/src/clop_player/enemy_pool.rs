@@ -1,6 +1,8 @@
 use super::player::{MinimaxParameters, MinimaxPlayer};
 use super::rave_player::RavePlayer;
-use super::mcts::RaveParameters;
+use crate::mcts::{FinalMove, PriorSource, RaveParameters};
+use crate::search::pruning::DEFAULT_PRUNING;
+use crate::search::quiescence::DEFAULT_QUIESCENCE;
 use game_sdk::{ClientListener, GameState, Move};
 
 const MINIMAX: MinimaxParameters = MinimaxParameters {
@@ -15,7 +17,19 @@ const MINIMAX: MinimaxParameters = MinimaxParameters {
     adj_border_end: -4.382,
     count_start: 6.681,
     count_end: 2.931,
+    adj_mobility_start: 0.,
+    adj_mobility_end: 0.,
+    adj_attacked_start: 0.,
+    adj_attacked_end: 0.,
+    adj_defended_start: 0.,
+    adj_defended_end: 0.,
+    adj_joining_start: 0.,
+    adj_joining_end: 0.,
+    adj_blocked_distances_start: 0.,
+    adj_blocked_distances_end: 0.,
     search_q: false,
+    quiescence: DEFAULT_QUIESCENCE,
+    pruning: DEFAULT_PRUNING,
 };
 
 const RAVE: RaveParameters = RaveParameters {
@@ -24,6 +38,12 @@ const RAVE: RaveParameters = RaveParameters {
     c_factor: 2.2,
     fpu_r: 1e-2,
     b_squared: 0.35,
+    puct: 0.,
+    prior: PriorSource::Variance,
+    prior_temperature: 50.,
+    final_move: FinalMove::BestValue,
+    widening: 0.,
+    widening_exponent: 0.,
 };
 
 pub enum Enemy {
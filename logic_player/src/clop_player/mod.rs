@@ -1,12 +1,9 @@
-mod mcts;
-mod piranhas;
 mod enemy_pool;
 pub use self::enemy_pool::EnemyPool;
 
 mod player;
 mod algorithm;
 mod evaluation;
-mod transposition;
 
 /*mod new_minimax;
 pub use self::new_minimax::NewMinimaxPlayer as ToClop;
@@ -17,4 +14,4 @@ pub use self::player::MinimaxParameters as ClopParameters;*/
 
 mod rave_player;
 pub use self::rave_player::RavePlayer as ToClop;
-pub use self::mcts::RaveParameters as ClopParameters;
\ No newline at end of file
+pub use crate::mcts::RaveParameters as ClopParameters;
\ No newline at end of file
@@ -4,10 +4,15 @@ use game_sdk::Move;
 use game_sdk::PlayerColor;
 
 use super::algorithm::{minimax_rate_state, SearchStatistics, MATE_SCORE};
-use super::transposition::TranspositionTable;
+use crate::eval::{Evaluator, TexelEvaluator, TexelWeights};
+use crate::limit::{SearchClock, SearchLimit, DEFAULT_LIMIT};
+use crate::search::ordering::MoveOrdering;
+use crate::search::pruning::PruningParameters;
+use crate::search::quiescence::QuiescenceParameters;
+use crate::search::transposition::{TranspositionTable, DEFAULT_SIZE_MB};
 
 use std::f32;
-use time;
+use std::sync::Arc;
 
 static START_DEPTH: u8 = 0;
 
@@ -24,7 +29,19 @@ pub struct MinimaxParameters {
 	pub adj_border_end: f32,
 	pub count_start: f32,
 	pub count_end: f32,
+	pub adj_mobility_start: f32,
+	pub adj_mobility_end: f32,
+	pub adj_attacked_start: f32,
+	pub adj_attacked_end: f32,
+	pub adj_defended_start: f32,
+	pub adj_defended_end: f32,
+	pub adj_joining_start: f32,
+	pub adj_joining_end: f32,
+	pub adj_blocked_distances_start: f32,
+	pub adj_blocked_distances_end: f32,
 	pub search_q: bool,
+	pub quiescence: QuiescenceParameters,
+	pub pruning: PruningParameters,
 }
 
 impl MinimaxParameters {
@@ -41,10 +58,51 @@ impl MinimaxParameters {
 			adj_border_end: 0.0,
 			count_start: 0.0,
 			count_end: 0.0,
+			adj_mobility_start: 0.0,
+			adj_mobility_end: 0.0,
+			adj_attacked_start: 0.0,
+			adj_attacked_end: 0.0,
+			adj_defended_start: 0.0,
+			adj_defended_end: 0.0,
+			adj_joining_start: 0.0,
+			adj_joining_end: 0.0,
+			adj_blocked_distances_start: 0.0,
+			adj_blocked_distances_end: 0.0,
 			search_q: true,
+			quiescence: QuiescenceParameters {
+				delta_margin: 0.0,
+				max_depth: 0,
+			},
+			pruning: PruningParameters::empty(),
 		}
 	}
 
+	/// The evaluation weights of these parameters
+	pub fn texel_weights(&self) -> TexelWeights {
+		return TexelWeights {
+			adj_distances_start: self.adj_distances_start,
+			adj_distances_end: self.adj_distances_end,
+			swarm_start: self.swarm_start,
+			swarm_end: self.swarm_end,
+			adj_center_start: self.adj_center_start,
+			adj_center_end: self.adj_center_end,
+			adj_border_start: self.adj_border_start,
+			adj_border_end: self.adj_border_end,
+			count_start: self.count_start,
+			count_end: self.count_end,
+			adj_mobility_start: self.adj_mobility_start,
+			adj_mobility_end: self.adj_mobility_end,
+			adj_attacked_start: self.adj_attacked_start,
+			adj_attacked_end: self.adj_attacked_end,
+			adj_defended_start: self.adj_defended_start,
+			adj_defended_end: self.adj_defended_end,
+			adj_joining_start: self.adj_joining_start,
+			adj_joining_end: self.adj_joining_end,
+			adj_blocked_distances_start: self.adj_blocked_distances_start,
+			adj_blocked_distances_end: self.adj_blocked_distances_end,
+		};
+	}
+
 	pub fn set_var_from_string(&mut self, identifier: String, val: String) {
 		match &identifier[..] {
 			"aspiration_window" => {
@@ -80,8 +138,46 @@ impl MinimaxParameters {
 			"count_end" => {
 				self.count_end = val.parse().expect("Got wrong val");
 			}
+			"adj_mobility_start" => {
+				self.adj_mobility_start = val.parse().expect("Got wrong val");
+			}
+			"adj_mobility_end" => {
+				self.adj_mobility_end = val.parse().expect("Got wrong val");
+			}
+			"adj_attacked_start" => {
+				self.adj_attacked_start = val.parse().expect("Got wrong val");
+			}
+			"adj_attacked_end" => {
+				self.adj_attacked_end = val.parse().expect("Got wrong val");
+			}
+			"adj_defended_start" => {
+				self.adj_defended_start = val.parse().expect("Got wrong val");
+			}
+			"adj_defended_end" => {
+				self.adj_defended_end = val.parse().expect("Got wrong val");
+			}
+			"adj_joining_start" => {
+				self.adj_joining_start = val.parse().expect("Got wrong val");
+			}
+			"adj_joining_end" => {
+				self.adj_joining_end = val.parse().expect("Got wrong val");
+			}
+			"adj_blocked_distances_start" => {
+				self.adj_blocked_distances_start = val.parse().expect("Got wrong val");
+			}
+			"adj_blocked_distances_end" => {
+				self.adj_blocked_distances_end = val.parse().expect("Got wrong val");
+			}
+			"q_delta_margin" => {
+				self.quiescence.delta_margin = val.parse().expect("Got wrong val");
+			}
+			"q_max_depth" => {
+				self.quiescence.max_depth = val.parse().expect("Got wrong val");
+			}
 			_ => {
-				panic!("Wrong identifier");
+				if !self.pruning.set_var_from_string(&identifier, &val) {
+					panic!("Wrong identifier");
+				}
 			}
 		};
 	}
@@ -90,20 +186,39 @@ impl MinimaxParameters {
 #[derive(Clone)]
 pub struct MinimaxPlayer {
 	tt: TranspositionTable,
+	ordering: MoveOrdering,
 	params: MinimaxParameters,
+	evaluator: Arc<dyn Evaluator>,
+	limit: SearchLimit,
 }
 impl MinimaxPlayer {
 	pub fn new(params: MinimaxParameters) -> MinimaxPlayer {
 		return MinimaxPlayer {
-			tt: TranspositionTable::new(),
+			tt: TranspositionTable::new(DEFAULT_SIZE_MB),
+			ordering: MoveOrdering::new(),
+			evaluator: Arc::new(TexelEvaluator::new(params.texel_weights())),
 			params,
+			limit: DEFAULT_LIMIT,
 		};
 	}
+
+	/// Ends every search at `limit`, with `SearchLimit::Nodes` the player repeats its games
+	pub fn set_limit(&mut self, limit: SearchLimit) {
+		self.limit = limit;
+	}
+
+	/// Replaces the evaluation the player searches with, a `TexelEvaluator` with the weights of
+	/// its parameters by default
+	pub fn set_evaluator(&mut self, evaluator: Arc<dyn Evaluator>) {
+		self.evaluator = evaluator;
+	}
 }
 
 impl ClientListener for MinimaxPlayer {
 	fn on_move_request(&mut self, state: &GameState) -> Move {
-		let before = time::now();
+		let clock = SearchClock::new(self.limit);
+		self.tt.new_search();
+		self.ordering.new_search();
 		let player_index = match state.get_current_player_color() {
 			PlayerColor::Red => 1,
 			PlayerColor::Blue => -1,
@@ -119,9 +234,8 @@ impl ClientListener for MinimaxPlayer {
 		let mut current_depth_best_move = None;
 		let color = state.get_current_player_color();
 		let mut search_stats = SearchStatistics::new();
-		let max_time = 1700;
 
-		while (time::now() - before).num_milliseconds() < max_time as i64 {
+		while !clock.expired(search_stats.nodes) {
 			search_stats.nodes += 1;
 			if current_depth == START_DEPTH {
 				let action_considered = playable_moves
@@ -137,11 +251,13 @@ impl ClientListener for MinimaxPlayer {
 					MATE_SCORE,
 					current_depth,
 					&mut self.tt,
-					&before,
-					max_time,
+					&mut self.ordering,
+					Some(*action_considered),
+					&clock,
 					&self.params,
+					&*self.evaluator,
 				);
-				if rate == f32::NAN {
+				if rate.is_nan() {
 					break;
 				}
 
@@ -163,11 +279,13 @@ impl ClientListener for MinimaxPlayer {
 					-best + self.params.aspiration_window,
 					current_depth,
 					&mut self.tt,
-					&before,
-					max_time,
+					&mut self.ordering,
+					Some(*action_considered),
+					&clock,
 					&self.params,
+					&*self.evaluator,
 				);
-				if rate == f32::NAN {
+				if rate.is_nan() {
 					break;
 				}
 				search_stats.aspire_probed += 1;
@@ -183,11 +301,13 @@ impl ClientListener for MinimaxPlayer {
 						MATE_SCORE,
 						current_depth - 1,
 						&mut self.tt,
-						&before,
-						max_time,
+						&mut self.ordering,
+						Some(*action_considered),
+						&clock,
 						&self.params,
+						&*self.evaluator,
 					);
-					if rate == f32::NAN {
+					if rate.is_nan() {
 						break;
 					}
 				}
@@ -208,11 +328,13 @@ impl ClientListener for MinimaxPlayer {
 					-current_depth_best,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
-					max_time,
+					&mut self.ordering,
+					Some(*action_considered),
+					&clock,
 					&self.params,
+					&*self.evaluator,
 				);
-				if rate == f32::NAN {
+				if rate.is_nan() {
 					break;
 				}
 				search_stats.probed += 1;
@@ -226,11 +348,13 @@ impl ClientListener for MinimaxPlayer {
 						MATE_SCORE,
 						current_depth - 1,
 						&mut self.tt,
-						&before,
-						max_time,
+						&mut self.ordering,
+						Some(*action_considered),
+						&clock,
 						&self.params,
+						&*self.evaluator,
 					);
-					if rate == f32::NAN {
+					if rate.is_nan() {
 						break;
 					}
 					current_depth_best = rate;
@@ -274,3 +398,27 @@ impl ClientListener for MinimaxPlayer {
 		}
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use game_sdk::gamerules;
+
+	#[test]
+	fn node_limit_still_finds_moves() {
+		let play = || {
+			let mut player = MinimaxPlayer::new(MinimaxParameters::empty());
+			player.set_limit(SearchLimit::Nodes(300));
+			let mut state = gamerules::get_seeded_state(4);
+			let mut moves = Vec::new();
+			for _ in 0..3 {
+				let action = player.on_move_request(&state);
+				let color = state.get_current_player_color();
+				state.perform(&action, &color);
+				moves.push(action);
+			}
+			return moves;
+		};
+		assert_eq!(play(), play());
+	}
+}
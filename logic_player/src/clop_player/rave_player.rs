@@ -1,37 +1,63 @@
-use super::mcts::{MCTS, RaveParameters};
-use super::piranhas::Piranhas;
+use crate::limit::{SearchClock, SearchLimit, DEFAULT_LIMIT};
+use crate::mcts::{Piranhas, RaveParameters, MCTS};
 use game_sdk::gamerules;
 use game_sdk::ClientListener;
 use game_sdk::{GameState, Move};
 
-use time;
-
 #[derive(Clone)]
 pub struct RavePlayer {
     mcts: Option<MCTS>,
     params: RaveParameters,
+    limit: SearchLimit,
+    seed: Option<u64>,
 }
 
 impl RavePlayer {
     pub fn new(params: RaveParameters) -> RavePlayer {
-        return RavePlayer { mcts: None, params };
+        return RavePlayer {
+            mcts: None,
+            params,
+            limit: DEFAULT_LIMIT,
+            seed: None,
+        };
+    }
+
+    /// Ends every search at `limit`, with `SearchLimit::Nodes` a seeded player repeats its
+    /// games
+    pub fn set_limit(&mut self, limit: SearchLimit) {
+        self.limit = limit;
+    }
+
+    /// Draws the random playout moves from a generator seeded with `seed`
+    pub fn set_seed(&mut self, seed: u64) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_seed(seed);
+        }
+        self.seed = Some(seed);
+    }
+
+    fn new_mcts(&self, game: &Piranhas) -> MCTS {
+        let mut mcts = MCTS::new(game, self.params.clone());
+        if let Some(seed) = self.seed {
+            mcts.set_seed(seed);
+        }
+        return mcts;
     }
 }
 
 impl ClientListener for RavePlayer {
     fn on_move_request(&mut self, state: &GameState) -> Move {
-        let before = time::now();
+        let clock = SearchClock::new(self.limit);
         let game = Piranhas::from_state(state);
         if let Some(ref mut mcts) = self.mcts {
             mcts.set_root(&game);
         // *mcts = MCTS::new(&game); // to deactivate taking knowledge over from last turn
         } else {
-            self.mcts = Some(MCTS::new(&game, self.params.clone()));
+            self.mcts = Some(self.new_mcts(&game));
         }
         if let Some(ref mut mcts) = self.mcts {
             // mcts.search(1_000, c);
-            let budget_seconds = 1.7 - ((time::now() - before).num_milliseconds() as f32 / 1000.);
-            mcts.search_time(budget_seconds);
+            mcts.search_until(&clock);
 
             if let (Some(action), _, _) = mcts.best_action() {
                 return action;
@@ -49,3 +75,28 @@ impl ClientListener for RavePlayer {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcts::LEGACY_RAVE;
+
+    #[test]
+    fn node_limit_repeats_the_moves() {
+        let play = || {
+            let mut player = RavePlayer::new(LEGACY_RAVE);
+            player.set_limit(SearchLimit::Nodes(300));
+            player.set_seed(7);
+            let mut state = gamerules::get_seeded_state(2);
+            let mut moves = Vec::new();
+            for _ in 0..3 {
+                let action = player.on_move_request(&state);
+                let color = state.get_current_player_color();
+                state.perform(&action, &color);
+                moves.push(action);
+            }
+            return moves;
+        };
+        assert_eq!(play(), play());
+    }
+}
@@ -0,0 +1,246 @@
+use game_sdk::GameState;
+use game_sdk::Move;
+use game_sdk::PlayerColor;
+
+use util::Helper;
+
+const CENTER_MASK: u128 = 297799908644072875622400;
+const BORDER_MASK: u128 = 1267033445369934637136782821375;
+
+/// Hand-crafted features of one color, the `ADJ_` variants are normalized by the fish count
+/// or by the 16 fishes every color starts with
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct FeatureVector {
+    pub distances: f32,
+    pub adj_distances: f32,
+    pub swarm_count: f32,
+    pub adj_swarm_count: f32,
+    pub center_count: f32,
+    pub adj_center_count: f32,
+    pub border_count: f32,
+    pub adj_border_count: f32,
+    pub count: f32,
+    pub adj_count: f32,
+    /// legal moves, as if `color` was to move
+    pub mobility: f32,
+    pub adj_mobility: f32,
+    /// fishes the opponent could capture
+    pub attacked: f32,
+    pub adj_attacked: f32,
+    /// fishes another own fish could recapture on
+    pub defended: f32,
+    pub adj_defended: f32,
+    /// moves of a fish outside the greatest swarm to a field next to it
+    pub joining_moves: f32,
+    pub adj_joining_moves: f32,
+    /// like `distances`, but paths may not cross enemy fishes
+    pub blocked_distances: f32,
+    pub adj_blocked_distances: f32,
+}
+
+/// Moves `color` could make in `state`, regardless of whose turn it is
+fn moves_of(state: &GameState, color: &PlayerColor) -> Vec<Move> {
+    if state.get_current_player_color() == *color {
+        return state.get_move_list();
+    }
+    let mut state = state.clone();
+    state.turn += 1;
+    return state.get_move_list();
+}
+
+/// Whether another fish of `color` could capture on `index` if an enemy stood there
+fn is_defended(state: &GameState, color: &PlayerColor, index: u8) -> bool {
+    let mut state = state.clone();
+    match color {
+        PlayerColor::Red => {
+            state.board.red_fields.clear_bit(index);
+            state.board.blue_fields.set_bit(index);
+        }
+        PlayerColor::Blue => {
+            state.board.blue_fields.clear_bit(index);
+            state.board.red_fields.set_bit(index);
+        }
+    }
+    return state.get_attack_board(color) & (1 << index) != 0;
+}
+
+/// Which of the features that need move generation or attack boards to extract, the
+/// others are cheap and always computed
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExtendedFeatures {
+    pub mobility: bool,
+    pub attacked: bool,
+    pub defended: bool,
+    pub joining_moves: bool,
+    pub blocked_distances: bool,
+}
+
+impl ExtendedFeatures {
+    pub const ALL: ExtendedFeatures = ExtendedFeatures {
+        mobility: true,
+        attacked: true,
+        defended: true,
+        joining_moves: true,
+        blocked_distances: true,
+    };
+}
+
+impl FeatureVector {
+    /// All features of `color`, as logged and tuned
+    pub fn extract(state: &GameState, color: &PlayerColor) -> FeatureVector {
+        return FeatureVector::extract_some(state, color, &ExtendedFeatures::ALL);
+    }
+
+    /// The features of `color`, the extended ones not in `extended` are left at 0
+    pub fn extract_some(
+        state: &GameState,
+        color: &PlayerColor,
+        extended: &ExtendedFeatures,
+    ) -> FeatureVector {
+        let swarm = Helper::greatest_swarm_new(state, color);
+        let mut distances: f32 = 0.;
+        let mut len = 0;
+        for (x, y) in state.get_own_fields(color) {
+            distances += 1. - (Helper::get_distance_to_swarm_new(x, y, &swarm) as f32 / 9.);
+            len += 1;
+        }
+        let count = len as f32;
+        let swarm_count = swarm.count_ones() as f32;
+
+        let enemy = state.board.get_fields_of(&color.get_opponent_color());
+        let mut blocked_distances: f32 = 0.;
+        let mut defended = 0;
+        if extended.blocked_distances || extended.defended {
+            for index in state.get_own_indices(color) {
+                if extended.blocked_distances {
+                    let distance = Helper::get_distance_to_swarm_alt(index, &swarm, &enemy).min(9);
+                    blocked_distances += 1. - distance as f32 / 9.;
+                }
+                if extended.defended && is_defended(state, color, index) {
+                    defended += 1;
+                }
+            }
+        }
+        let defended = defended as f32;
+
+        let mut mobility = 0.;
+        let mut joining_moves = 0.;
+        if extended.mobility || extended.joining_moves {
+            let moves = moves_of(state, color);
+            mobility = moves.len() as f32;
+            joining_moves = moves
+                .iter()
+                .filter(|action| {
+                    !swarm.is_field_set(action.x, action.y)
+                        && Helper::get_distance_to_swarm_new(action.dest_x, action.dest_y, &swarm)
+                            == 1
+                })
+                .count() as f32;
+        }
+        let mut attacked = 0.;
+        if extended.attacked {
+            attacked = (state.get_attack_board(&color.get_opponent_color())
+                & state.board.get_fields_of(color).bits)
+                .count_ones() as f32;
+        }
+
+        let fishes = state.board.get_fields_of(color);
+        let mut center = fishes;
+        center.mask(CENTER_MASK);
+        let center_count = center.count_ones() as f32;
+        let mut border = fishes;
+        border.mask(BORDER_MASK);
+        let border_count = border.count_ones() as f32;
+
+        return FeatureVector {
+            distances,
+            adj_distances: distances / count,
+            swarm_count,
+            adj_swarm_count: swarm_count / 16.,
+            center_count,
+            adj_center_count: center_count / count,
+            border_count,
+            adj_border_count: border_count / count,
+            count,
+            adj_count: count / 16.,
+            mobility,
+            adj_mobility: mobility / count,
+            attacked,
+            adj_attacked: attacked / count,
+            defended,
+            adj_defended: defended / count,
+            joining_moves,
+            adj_joining_moves: joining_moves / count,
+            blocked_distances,
+            adj_blocked_distances: blocked_distances / count,
+        };
+    }
+
+    /// Feature names as used in the logged data, paired with their values
+    pub fn named(&self) -> Vec<(&'static str, f32)> {
+        return vec![
+            ("DISTANCES", self.distances),
+            ("ADJ_DISTANCES", self.adj_distances),
+            ("SWARM_COUNT", self.swarm_count),
+            ("ADJ_SWARM_COUNT", self.adj_swarm_count),
+            ("CENTER_COUNT", self.center_count),
+            ("ADJ_CENTER_COUNT", self.adj_center_count),
+            ("BORDER_COUNT", self.border_count),
+            ("ADJ_BORDER_COUNT", self.adj_border_count),
+            ("COUNT", self.count),
+            ("ADJ_COUNT", self.adj_count),
+            ("MOBILITY", self.mobility),
+            ("ADJ_MOBILITY", self.adj_mobility),
+            ("ATTACKED", self.attacked),
+            ("ADJ_ATTACKED", self.adj_attacked),
+            ("DEFENDED", self.defended),
+            ("ADJ_DEFENDED", self.adj_defended),
+            ("JOINING_MOVES", self.joining_moves),
+            ("ADJ_JOINING_MOVES", self.adj_joining_moves),
+            ("BLOCKED_DISTANCES", self.blocked_distances),
+            ("ADJ_BLOCKED_DISTANCES", self.adj_blocked_distances),
+        ];
+    }
+
+    /// Reads the features of `color` back from logged `data`, `None` if one is missing.
+    /// Data logged before the mobility and blocking features existed reads them as 0
+    pub fn from_data(data: &[(String, f32)], color: &PlayerColor) -> Option<FeatureVector> {
+        let get = |name: &str| {
+            let key = format!("{}-{}", color, name);
+            return data.iter().find(|(k, _)| *k == key).map(|&(_, v)| v);
+        };
+        return Some(FeatureVector {
+            distances: get("DISTANCES")?,
+            adj_distances: get("ADJ_DISTANCES")?,
+            swarm_count: get("SWARM_COUNT")?,
+            adj_swarm_count: get("ADJ_SWARM_COUNT")?,
+            center_count: get("CENTER_COUNT")?,
+            adj_center_count: get("ADJ_CENTER_COUNT")?,
+            border_count: get("BORDER_COUNT")?,
+            adj_border_count: get("ADJ_BORDER_COUNT")?,
+            count: get("COUNT")?,
+            adj_count: get("ADJ_COUNT")?,
+            mobility: get("MOBILITY").unwrap_or(0.),
+            adj_mobility: get("ADJ_MOBILITY").unwrap_or(0.),
+            attacked: get("ATTACKED").unwrap_or(0.),
+            adj_attacked: get("ADJ_ATTACKED").unwrap_or(0.),
+            defended: get("DEFENDED").unwrap_or(0.),
+            adj_defended: get("ADJ_DEFENDED").unwrap_or(0.),
+            joining_moves: get("JOINING_MOVES").unwrap_or(0.),
+            adj_joining_moves: get("ADJ_JOINING_MOVES").unwrap_or(0.),
+            blocked_distances: get("BLOCKED_DISTANCES").unwrap_or(0.),
+            adj_blocked_distances: get("ADJ_BLOCKED_DISTANCES").unwrap_or(0.),
+        });
+    }
+
+    /// Features of both colors keyed like `RED-SWARM_COUNT`, the format of `State::data`
+    pub fn data_vec(state: &GameState) -> Vec<(String, f32)> {
+        let mut result = Vec::new();
+        for color in [PlayerColor::Red, PlayerColor::Blue].iter() {
+            for (name, value) in FeatureVector::extract(state, color).named() {
+                result.push((format!("{}-{}", color, name), value));
+            }
+        }
+        return result;
+    }
+}
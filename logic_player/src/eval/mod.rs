@@ -0,0 +1,143 @@
+//! Static evaluations the search engines can be run with, independent of any player
+
+mod features;
+mod network;
+mod texel;
+
+pub use self::features::{ExtendedFeatures, FeatureVector};
+pub use self::network::{
+    encode, Activation, Layer, Network, NetworkEvaluator, INPUTS, POLICY_OUTPUTS,
+};
+pub use self::texel::{texel_feature, TexelEvaluator, TexelWeights, DEFAULT_TEXEL};
+
+use game_sdk::{GameState, PlayerColor};
+
+/// A static evaluation, positive values are good for red.
+///
+/// Engines hold it behind an `Arc`, so one evaluator can be shared by all players of a
+/// simulation.
+pub trait Evaluator: Send + Sync {
+    fn evaluate(&self, state: &GameState) -> f32;
+
+    /// The terms `evaluate` is made of, their contributions add up to its result
+    fn explain(&self, state: &GameState) -> Vec<Term> {
+        let value = self.evaluate(state);
+        return vec![Term {
+            name: "TOTAL",
+            color: None,
+            raw: value,
+            contribution: value,
+        }];
+    }
+}
+
+/// One term of an evaluation
+#[derive(Clone, Debug)]
+pub struct Term {
+    pub name: &'static str,
+    /// color the term was computed for, `None` if it concerns both
+    pub color: Option<PlayerColor>,
+    pub raw: f32,
+    /// what the term adds to the evaluation after weighting, from red's point of view
+    pub contribution: f32,
+}
+
+/// Terms of a tapered evaluation of `color`, each feature given as name, raw value and the
+/// weights at the start and the end of the game
+pub fn tapered_terms(
+    features: &[(&'static str, f32, f32, f32)],
+    color: &PlayerColor,
+    phase: f32,
+) -> Vec<Term> {
+    let sign = match color {
+        PlayerColor::Red => 1.,
+        PlayerColor::Blue => -1.,
+    };
+    return features
+        .iter()
+        .map(|&(name, raw, start, end)| Term {
+            name,
+            color: Some(*color),
+            raw,
+            contribution: sign * texel_feature(phase, raw, start, end),
+        })
+        .collect();
+}
+
+/// 0 at the start of the game, 1 at the turn limit
+pub fn phase(state: &GameState) -> f32 {
+    return phase_of_turn(state.turn);
+}
+
+pub fn phase_of_turn(turn: u8) -> f32 {
+    return turn as f32 / 60.;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::{gamerules, PlayerColor};
+
+    #[test]
+    fn texel_is_symmetric() {
+        let state = gamerules::get_seeded_state(1);
+        let evaluator = TexelEvaluator::new(DEFAULT_TEXEL);
+        let red = DEFAULT_TEXEL.rate_color(&state, &PlayerColor::Red);
+        let blue = DEFAULT_TEXEL.rate_color(&state, &PlayerColor::Blue);
+        assert_eq!(evaluator.evaluate(&state), red - blue);
+        let terms = evaluator.explain(&state);
+        assert_eq!(terms.len(), 20);
+        let sum: f32 = terms.iter().map(|term| term.contribution).sum();
+        assert!((sum - (red - blue)).abs() < 1e-4);
+    }
+
+    #[test]
+    fn features_of_both_colors() {
+        let state = gamerules::get_seeded_state(2);
+        let data = FeatureVector::data_vec(&state);
+        assert_eq!(data.len(), 40);
+        let features = FeatureVector::extract(&state, &PlayerColor::Red);
+        assert_eq!(features.count, 16.);
+        assert_eq!(features.adj_count, 1.);
+        assert_eq!(
+            FeatureVector::from_data(&data, &PlayerColor::Red),
+            Some(features)
+        );
+        assert_eq!(FeatureVector::from_data(&[], &PlayerColor::Blue), None);
+    }
+
+    #[test]
+    fn mobility_of_both_colors() {
+        let mut state = gamerules::get_seeded_state(3);
+        for _ in 0..2 {
+            let color = state.get_current_player_color();
+            let features = FeatureVector::extract(&state, &color);
+            assert_eq!(features.mobility, state.get_move_list().len() as f32);
+            let other = FeatureVector::extract(&state, &color.get_opponent_color());
+            state.turn += 1;
+            assert_eq!(
+                other,
+                FeatureVector::extract(&state, &color.get_opponent_color())
+            );
+        }
+    }
+
+    #[test]
+    fn extracts_only_weighted_features() {
+        let state = gamerules::get_seeded_state(4);
+        let all = FeatureVector::extract(&state, &PlayerColor::Red);
+        let mut weights = DEFAULT_TEXEL;
+        weights.adj_mobility_end = 1.;
+        let extended = weights.extended_features();
+        assert!(extended.mobility && !extended.defended);
+        let some = FeatureVector::extract_some(&state, &PlayerColor::Red, &extended);
+        assert_eq!(some.mobility, all.mobility);
+        assert_eq!(some.defended, 0.);
+        assert_eq!(some.swarm_count, all.swarm_count);
+        let phase = phase(&state);
+        assert_eq!(
+            weights.rate_color(&state, &PlayerColor::Red),
+            weights.rate_features(&all, phase)
+        );
+    }
+}
@@ -0,0 +1,314 @@
+use game_sdk::{GameState, Move, PlayerColor};
+
+use super::{phase, Evaluator};
+
+use std::fs;
+
+const FIELDS: usize = 100;
+/// own fishes, enemy fishes and obstacles, one plane each, plus the phase
+pub const INPUTS: usize = 3 * FIELDS + 1;
+/// Outputs of a network with a policy head: the value, then one logit per origin field and
+/// one per destination field
+pub const POLICY_OUTPUTS: usize = 1 + 2 * FIELDS;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Activation {
+    Linear,
+    Relu,
+    Tanh,
+}
+
+impl Activation {
+    fn from_name(name: &str) -> Result<Activation, String> {
+        return match name {
+            "linear" => Ok(Activation::Linear),
+            "relu" => Ok(Activation::Relu),
+            "tanh" => Ok(Activation::Tanh),
+            _ => Err(format!("Unknown activation {}", name)),
+        };
+    }
+
+    pub fn name(&self) -> &'static str {
+        return match self {
+            Activation::Linear => "linear",
+            Activation::Relu => "relu",
+            Activation::Tanh => "tanh",
+        };
+    }
+
+    fn apply(&self, x: f32) -> f32 {
+        return match self {
+            Activation::Linear => x,
+            Activation::Relu => f32::max(0., x),
+            Activation::Tanh => x.tanh(),
+        };
+    }
+}
+
+/// Fully connected layer, `weights` holds one row of `inputs` weights per output
+#[derive(Clone, Debug)]
+pub struct Layer {
+    pub inputs: usize,
+    pub outputs: usize,
+    pub weights: Vec<f32>,
+    pub biases: Vec<f32>,
+    pub activation: Activation,
+}
+
+impl Layer {
+    pub fn forward(&self, input: &[f32]) -> Vec<f32> {
+        let mut output = self.biases.clone();
+        for (o, value) in output.iter_mut().enumerate() {
+            let row = &self.weights[o * self.inputs..(o + 1) * self.inputs];
+            for (weight, x) in row.iter().zip(input.iter()) {
+                *value += weight * x;
+            }
+            *value = self.activation.apply(*value);
+        }
+        return output;
+    }
+}
+
+/// A small multilayer perceptron evaluated on the CPU.
+///
+/// The network sees the board from the side to move. Its first output is the value, squashed
+/// with tanh and multiplied by `scale` to match the range of the hand-crafted evaluations.
+/// Networks with `POLICY_OUTPUTS` outputs also rate moves, see `policy`.
+///
+/// The weights file is plain text: a `scale <value>` line, then for every layer a
+/// `layer <inputs> <outputs> <activation>` line followed by the weights row by row and the
+/// biases. Lines starting with `#` are ignored.
+#[derive(Clone, Debug)]
+pub struct Network {
+    pub scale: f32,
+    pub layers: Vec<Layer>,
+}
+
+impl Network {
+    pub fn from_file(path: &str) -> Result<Network, String> {
+        let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+        return Network::parse(&content);
+    }
+
+    pub fn parse(content: &str) -> Result<Network, String> {
+        let mut words = content
+            .lines()
+            .filter(|line| !line.trim_start().starts_with('#'))
+            .flat_map(|line| line.split_whitespace());
+        let mut scale = 1.;
+        let mut layers: Vec<Layer> = Vec::new();
+        while let Some(word) = words.next() {
+            match word {
+                "scale" => scale = next_number(&mut words)?,
+                "layer" => {
+                    let inputs: usize = next_number(&mut words)?;
+                    let outputs: usize = next_number(&mut words)?;
+                    let activation =
+                        Activation::from_name(words.next().ok_or("Missing activation")?)?;
+                    let expected = layers.last().map_or(INPUTS, |layer| layer.outputs);
+                    if inputs != expected {
+                        return Err(format!(
+                            "Layer has {} inputs, expected {}",
+                            inputs, expected
+                        ));
+                    }
+                    let mut weights = Vec::with_capacity(inputs * outputs);
+                    for _ in 0..inputs * outputs {
+                        weights.push(next_number(&mut words)?);
+                    }
+                    let mut biases = Vec::with_capacity(outputs);
+                    for _ in 0..outputs {
+                        biases.push(next_number(&mut words)?);
+                    }
+                    layers.push(Layer {
+                        inputs,
+                        outputs,
+                        weights,
+                        biases,
+                        activation,
+                    });
+                }
+                _ => return Err(format!("Unexpected {}", word)),
+            }
+        }
+        match layers.last() {
+            None => return Err("Network has no layers".to_string()),
+            Some(layer) if layer.outputs != 1 && layer.outputs != POLICY_OUTPUTS => {
+                return Err(format!("Network has {} outputs", layer.outputs));
+            }
+            _ => {}
+        }
+        return Ok(Network { scale, layers });
+    }
+
+    /// Writes the network in the format `parse` reads
+    pub fn to_text(&self) -> String {
+        let mut text = format!("scale {}\n", self.scale);
+        for layer in self.layers.iter() {
+            text += &format!(
+                "layer {} {} {}\n",
+                layer.inputs,
+                layer.outputs,
+                layer.activation.name()
+            );
+            for row in layer.weights.chunks(layer.inputs) {
+                text += &join(row);
+            }
+            text += &join(&layer.biases);
+        }
+        return text;
+    }
+
+    pub fn has_policy(&self) -> bool {
+        return self.layers[self.layers.len() - 1].outputs == POLICY_OUTPUTS;
+    }
+
+    pub fn forward(&self, input: &[f32]) -> Vec<f32> {
+        let mut values = input.to_vec();
+        for layer in self.layers.iter() {
+            values = layer.forward(&values);
+        }
+        return values;
+    }
+
+    /// Value of `state` for the side to move
+    pub fn value(&self, state: &GameState) -> f32 {
+        return self.scale * self.forward(&encode(state))[0].tanh();
+    }
+
+    /// Prior probabilities of `moves` in `state`. The logits of a move's origin and destination
+    /// are added up and normalized with a softmax, without a policy head every move is as likely
+    pub fn policy(&self, state: &GameState, moves: &[Move]) -> Vec<f32> {
+        if moves.is_empty() {
+            return Vec::new();
+        }
+        if !self.has_policy() {
+            return vec![1. / moves.len() as f32; moves.len()];
+        }
+        let output = self.forward(&encode(state));
+        let logits: Vec<f32> = moves
+            .iter()
+            .map(|action| {
+                let from = action.x as usize + action.y as usize * 10;
+                let to = action.dest_x as usize + action.dest_y as usize * 10;
+                output[1 + from] + output[1 + FIELDS + to]
+            })
+            .collect();
+        let max = logits.iter().cloned().fold(f32::MIN, f32::max);
+        let exponentials: Vec<f32> = logits.iter().map(|logit| (logit - max).exp()).collect();
+        let sum: f32 = exponentials.iter().sum();
+        return exponentials.iter().map(|e| e / sum).collect();
+    }
+}
+
+fn next_number<'a, T, I>(words: &mut I) -> Result<T, String>
+where
+    T: std::str::FromStr,
+    I: Iterator<Item = &'a str>,
+{
+    let word = words.next().ok_or("Unexpected end of weights file")?;
+    return word.parse().map_err(|_| format!("{} is no number", word));
+}
+
+fn join(values: &[f32]) -> String {
+    let strings: Vec<String> = values.iter().map(|v| v.to_string()).collect();
+    return strings.join(" ") + "\n";
+}
+
+/// Input of the network for `state`, seen from the side to move
+pub fn encode(state: &GameState) -> Vec<f32> {
+    let color = state.get_current_player_color();
+    let planes = [
+        state.board.get_fields_of(&color).bits,
+        state.board.get_fields_of(&color.get_opponent_color()).bits,
+        state.board.obstacle_fields.bits,
+    ];
+    let mut input = Vec::with_capacity(INPUTS);
+    for bits in planes.iter() {
+        for i in 0..FIELDS {
+            input.push(((bits >> i) & 1) as f32);
+        }
+    }
+    input.push(phase(state));
+    return input;
+}
+
+/// Leaf evaluation by a `Network`
+#[derive(Clone)]
+pub struct NetworkEvaluator {
+    pub network: Network,
+}
+
+impl NetworkEvaluator {
+    pub fn new(network: Network) -> NetworkEvaluator {
+        return NetworkEvaluator { network };
+    }
+}
+
+impl Evaluator for NetworkEvaluator {
+    fn evaluate(&self, state: &GameState) -> f32 {
+        let value = self.network.value(state);
+        return match state.get_current_player_color() {
+            PlayerColor::Red => value,
+            PlayerColor::Blue => -value,
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::gamerules;
+
+    fn tiny_network(outputs: usize) -> Network {
+        let hidden = Layer {
+            inputs: INPUTS,
+            outputs: 2,
+            weights: (0..2 * INPUTS).map(|i| (i % 7) as f32 * 0.01).collect(),
+            biases: vec![0.1, -0.1],
+            activation: Activation::Relu,
+        };
+        let output = Layer {
+            inputs: 2,
+            outputs,
+            weights: (0..2 * outputs)
+                .map(|i| (i % 5) as f32 * 0.1 - 0.2)
+                .collect(),
+            biases: vec![0.; outputs],
+            activation: Activation::Linear,
+        };
+        return Network {
+            scale: 40.,
+            layers: vec![hidden, output],
+        };
+    }
+
+    #[test]
+    fn text_round_trip() {
+        let network = tiny_network(1);
+        let parsed = Network::parse(&network.to_text()).expect("Could not parse network");
+        let state = gamerules::get_seeded_state(1);
+        assert_eq!(parsed.value(&state), network.value(&state));
+        assert!(Network::parse("scale 1\nlayer 3 1 relu\n1 2 3 0").is_err());
+    }
+
+    #[test]
+    fn policy_is_distribution() {
+        let state = gamerules::get_seeded_state(2);
+        let moves = state.get_move_list();
+        for network in [tiny_network(1), tiny_network(POLICY_OUTPUTS)].iter() {
+            let policy = network.policy(&state, &moves);
+            assert_eq!(policy.len(), moves.len());
+            assert!((policy.iter().sum::<f32>() - 1.).abs() < 1e-4);
+        }
+        let evaluator = NetworkEvaluator::new(tiny_network(1));
+        let sign = match state.get_current_player_color() {
+            PlayerColor::Red => 1.,
+            PlayerColor::Blue => -1.,
+        };
+        assert_eq!(
+            evaluator.evaluate(&state),
+            sign * evaluator.network.value(&state)
+        );
+    }
+}
@@ -0,0 +1,189 @@
+use game_sdk::GameState;
+use game_sdk::PlayerColor;
+
+use super::{phase, tapered_terms, Evaluator, ExtendedFeatures, FeatureVector, Term};
+
+/// Weights of a tapered linear evaluation, each feature has one for the start and one for
+/// the end of the game and is interpolated in between
+#[derive(Clone, Copy, Debug)]
+pub struct TexelWeights {
+    pub adj_distances_start: f32,
+    pub adj_distances_end: f32,
+    pub swarm_start: f32,
+    pub swarm_end: f32,
+    pub adj_center_start: f32,
+    pub adj_center_end: f32,
+    pub adj_border_start: f32,
+    pub adj_border_end: f32,
+    pub count_start: f32,
+    pub count_end: f32,
+    pub adj_mobility_start: f32,
+    pub adj_mobility_end: f32,
+    pub adj_attacked_start: f32,
+    pub adj_attacked_end: f32,
+    pub adj_defended_start: f32,
+    pub adj_defended_end: f32,
+    pub adj_joining_start: f32,
+    pub adj_joining_end: f32,
+    pub adj_blocked_distances_start: f32,
+    pub adj_blocked_distances_end: f32,
+}
+
+pub const DEFAULT_TEXEL: TexelWeights = TexelWeights {
+    adj_distances_start: 1.816,
+    adj_distances_end: 7.583,
+    swarm_start: -3.389,
+    swarm_end: 6.421,
+    adj_center_start: -0.033,
+    adj_center_end: 3.777,
+    adj_border_start: -8.652,
+    adj_border_end: -0.648,
+    count_start: 5.228,
+    count_end: 4.445,
+    adj_mobility_start: 0.,
+    adj_mobility_end: 0.,
+    adj_attacked_start: 0.,
+    adj_attacked_end: 0.,
+    adj_defended_start: 0.,
+    adj_defended_end: 0.,
+    adj_joining_start: 0.,
+    adj_joining_end: 0.,
+    adj_blocked_distances_start: 0.,
+    adj_blocked_distances_end: 0.,
+};
+
+pub fn texel_feature(phase: f32, x: f32, start: f32, end: f32) -> f32 {
+    return phase * x * end + (1. - phase) * x * start;
+}
+
+impl TexelWeights {
+    pub fn rate_features(&self, features: &FeatureVector, phase: f32) -> f32 {
+        let mut result = 0.0;
+        for &(_, x, start, end) in self.weighted_features(features).iter() {
+            result += texel_feature(phase, x, start, end);
+        }
+        return result;
+    }
+
+    /// Skips the extended features these weights ignore, so the default weights rate a
+    /// position as fast as before those features existed
+    pub fn rate_color(&self, state: &GameState, color: &PlayerColor) -> f32 {
+        let features = FeatureVector::extract_some(state, color, &self.extended_features());
+        return self.rate_features(&features, phase(state));
+    }
+
+    /// The extended features with a weight other than 0
+    pub fn extended_features(&self) -> ExtendedFeatures {
+        let used = |start: f32, end: f32| start != 0. || end != 0.;
+        return ExtendedFeatures {
+            mobility: used(self.adj_mobility_start, self.adj_mobility_end),
+            attacked: used(self.adj_attacked_start, self.adj_attacked_end),
+            defended: used(self.adj_defended_start, self.adj_defended_end),
+            joining_moves: used(self.adj_joining_start, self.adj_joining_end),
+            blocked_distances: used(
+                self.adj_blocked_distances_start,
+                self.adj_blocked_distances_end,
+            ),
+        };
+    }
+
+    /// The features `rate_features` uses with their start and end weights
+    pub fn weighted_features(
+        &self,
+        features: &FeatureVector,
+    ) -> [(&'static str, f32, f32, f32); 10] {
+        return [
+            (
+                "ADJ_DISTANCES",
+                features.adj_distances,
+                self.adj_distances_start,
+                self.adj_distances_end,
+            ),
+            (
+                "ADJ_SWARM_COUNT",
+                features.adj_swarm_count,
+                self.swarm_start,
+                self.swarm_end,
+            ),
+            (
+                "ADJ_CENTER_COUNT",
+                features.adj_center_count,
+                self.adj_center_start,
+                self.adj_center_end,
+            ),
+            (
+                "ADJ_BORDER_COUNT",
+                features.adj_border_count,
+                self.adj_border_start,
+                self.adj_border_end,
+            ),
+            (
+                "ADJ_COUNT",
+                features.adj_count,
+                self.count_start,
+                self.count_end,
+            ),
+            (
+                "ADJ_MOBILITY",
+                features.adj_mobility,
+                self.adj_mobility_start,
+                self.adj_mobility_end,
+            ),
+            (
+                "ADJ_ATTACKED",
+                features.adj_attacked,
+                self.adj_attacked_start,
+                self.adj_attacked_end,
+            ),
+            (
+                "ADJ_DEFENDED",
+                features.adj_defended,
+                self.adj_defended_start,
+                self.adj_defended_end,
+            ),
+            (
+                "ADJ_JOINING_MOVES",
+                features.adj_joining_moves,
+                self.adj_joining_start,
+                self.adj_joining_end,
+            ),
+            (
+                "ADJ_BLOCKED_DISTANCES",
+                features.adj_blocked_distances,
+                self.adj_blocked_distances_start,
+                self.adj_blocked_distances_end,
+            ),
+        ];
+    }
+}
+
+#[derive(Clone)]
+pub struct TexelEvaluator {
+    pub weights: TexelWeights,
+}
+
+impl TexelEvaluator {
+    pub fn new(weights: TexelWeights) -> TexelEvaluator {
+        return TexelEvaluator { weights };
+    }
+}
+
+impl Evaluator for TexelEvaluator {
+    fn evaluate(&self, state: &GameState) -> f32 {
+        return self.weights.rate_color(state, &PlayerColor::Red)
+            - self.weights.rate_color(state, &PlayerColor::Blue);
+    }
+
+    fn explain(&self, state: &GameState) -> Vec<Term> {
+        let mut terms = Vec::new();
+        for color in [PlayerColor::Red, PlayerColor::Blue].iter() {
+            let features = FeatureVector::extract(state, color);
+            terms.extend(tapered_terms(
+                &self.weights.weighted_features(&features),
+                color,
+                phase(state),
+            ));
+        }
+        return terms;
+    }
+}
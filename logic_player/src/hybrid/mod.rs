@@ -1,4 +1,6 @@
 mod player;
+mod policy;
 
 pub use self::player::HybridPlayer;
-pub use self::player::LegacyHybridPlayer;
\ No newline at end of file
+pub use self::player::LegacyHybridPlayer;
+pub use self::policy::{SwitchPolicy, DEFAULT_POLICY};
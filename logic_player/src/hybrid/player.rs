@@ -1,12 +1,64 @@
-use game_sdk::logging::{Data};
+use game_sdk::logging::{send_report, Data, SearchReport};
 use game_sdk::{ClientListener, GameState, Move};
 
 use crate::MinimaxPlayer;
 use crate::RavePlayer;
 use crate::LegacyRavePlayer;
 use crate::LegacyMinimaxPlayer;
+use super::policy::{SwitchPolicy, DEFAULT_POLICY};
+use crate::book::OpeningBook;
+use crate::limit::SearchLimit;
+use crate::search::solver::{Outcome, Solver, SolverParameters, DEFAULT_SOLVER};
 
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
+use time;
+
+/// The move of the proven outcome if `state` is close to the end and the solver finishes in
+/// time, reported like the searches do
+fn solved_move(
+	solver: &mut Solver,
+	state: &GameState,
+	tx: &Option<mpsc::Sender<Data>>,
+	id: i64,
+) -> Option<Move> {
+	if !solver.applies(state) {
+		return None;
+	}
+	let before = time::now();
+	let solution = solver.solve(state)?;
+	let report = SearchReport {
+		engine: "solver".to_string(),
+		id,
+		turn: state.turn,
+		time_ms: (time::now() - before).num_milliseconds(),
+		action: solution.action,
+		score: match solution.outcome {
+			Outcome::Win(_) => 1.,
+			Outcome::Draw => 0.5,
+			Outcome::Loss(_) => 0.,
+		},
+		depth: None,
+		nodes: solver.nodes as u64,
+		proven: Some(format!("{:?}", solution.outcome)),
+		pv: solution.action.into_iter().collect(),
+		moves: Vec::new(),
+		stats: Vec::new(),
+	};
+	send_report(tx, report);
+	return solution.action;
+}
+
+/// The solver of a hybrid searching with `limit`. Under a node limit it may only run out of
+/// nodes, not of time, so its moves stay repeatable
+fn solver_for(limit: SearchLimit) -> Solver {
+	return Solver::new(match limit {
+		SearchLimit::Millis(_) => DEFAULT_SOLVER,
+		SearchLimit::Nodes(_) => SolverParameters {
+			max_millis: i64::MAX,
+			..DEFAULT_SOLVER
+		},
+	});
+}
 
 #[derive(Clone)]
 pub struct HybridPlayer {
@@ -14,24 +66,59 @@ pub struct HybridPlayer {
 	tx: Option<mpsc::Sender<Data>>,
 	mcts: RavePlayer,
 	ab: MinimaxPlayer,
+	solver: Solver,
+	policy: SwitchPolicy,
 }
 impl HybridPlayer {
 	pub fn new(tx: Option<mpsc::Sender<Data>>, id: i64) -> HybridPlayer {
+		return HybridPlayer::with_policy(tx, id, DEFAULT_POLICY);
+	}
+
+	pub fn with_policy(tx: Option<mpsc::Sender<Data>>, id: i64, policy: SwitchPolicy) -> HybridPlayer {
 		return HybridPlayer {
 			id,
 			tx: tx.clone(),
 			mcts: RavePlayer::new(tx.clone(), id),
 			ab: MinimaxPlayer::new(tx, id),
+			solver: Solver::new(DEFAULT_SOLVER),
+			policy,
 		};
 	}
 }
 
 impl HybridPlayer {
+	/// Lets both searches play from `book`, see `OpeningBook::lookup`
+	pub fn set_book(&mut self, book: Arc<OpeningBook>, randomness: f32) {
+		self.mcts.set_book(book.clone(), randomness);
+		self.ab.set_book(book, randomness);
+	}
+
+	/// Ends the searches of both engines and the solver at `limit`
+	pub fn set_limit(&mut self, limit: SearchLimit) {
+		self.mcts.set_limit(limit);
+		self.ab.set_limit(limit);
+		self.solver = solver_for(limit);
+	}
+
+	/// Seeds the random choices of both engines with `seed`
+	pub fn set_seed(&mut self, seed: u64) {
+		self.mcts.set_seed(seed);
+		self.ab.set_seed(seed);
+	}
+
 	pub fn move_with_id(&mut self, state: &GameState, id: i64) -> Move {
-		if state.turn > 19 {
-			return self.ab.move_with_id(state, id);
+		// the time the solver took counts against the move of the engine
+		let start = time::now();
+		if let Some(action) = solved_move(&mut self.solver, state, &self.tx, id) {
+			return action;
 		}
-		return self.mcts.move_with_id(state, id);
+		let mcts = &mut self.mcts;
+		let ab = &mut self.ab;
+		return self.policy.choose(
+			state,
+			|| (mcts.move_since(state, id, start), mcts.confidence()),
+			|| ab.move_since(state, id, start),
+		);
 	}
 }
 
@@ -48,24 +135,52 @@ pub struct LegacyHybridPlayer {
 	tx: Option<mpsc::Sender<Data>>,
 	mcts: LegacyRavePlayer,
 	ab: LegacyMinimaxPlayer,
+	solver: Solver,
+	policy: SwitchPolicy,
 }
 impl LegacyHybridPlayer {
 	pub fn new(tx: Option<mpsc::Sender<Data>>, id: i64) -> LegacyHybridPlayer {
+		return LegacyHybridPlayer::with_policy(tx, id, DEFAULT_POLICY);
+	}
+
+	pub fn with_policy(tx: Option<mpsc::Sender<Data>>, id: i64, policy: SwitchPolicy) -> LegacyHybridPlayer {
 		return LegacyHybridPlayer {
 			id,
 			tx: tx.clone(),
 			mcts: LegacyRavePlayer::new(tx.clone(), id),
 			ab: LegacyMinimaxPlayer::new(tx, id),
+			solver: Solver::new(DEFAULT_SOLVER),
+			policy,
 		};
 	}
 }
 
 impl LegacyHybridPlayer {
+	/// Ends the searches of both engines and the solver at `limit`
+	pub fn set_limit(&mut self, limit: SearchLimit) {
+		self.mcts.set_limit(limit);
+		self.ab.set_limit(limit);
+		self.solver = solver_for(limit);
+	}
+
+	/// Seeds the random playout moves with `seed`, the alpha-beta search makes no random choices
+	pub fn set_seed(&mut self, seed: u64) {
+		self.mcts.set_seed(seed);
+	}
+
 	pub fn move_with_id(&mut self, state: &GameState, id: i64) -> Move {
-		if state.turn > 19 {
-			return self.ab.move_with_id(state, id);
+		// the time the solver took counts against the move of the engine
+		let start = time::now();
+		if let Some(action) = solved_move(&mut self.solver, state, &self.tx, id) {
+			return action;
 		}
-		return self.mcts.move_with_id(state, id);
+		let mcts = &mut self.mcts;
+		let ab = &mut self.ab;
+		return self.policy.choose(
+			state,
+			|| (mcts.move_since(state, id, start), mcts.confidence()),
+			|| ab.move_since(state, id, start),
+		);
 	}
 }
 
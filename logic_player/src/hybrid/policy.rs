@@ -0,0 +1,122 @@
+use game_sdk::{GameState, Move};
+
+/// When a hybrid player hands the game from MCTS over to alpha-beta
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SwitchPolicy {
+    /// alpha-beta from this turn on
+    Turn(u8),
+    /// alpha-beta once the side to move has at most this many fishes
+    Fishes(u32),
+    /// alpha-beta once there are at most this many legal moves
+    Branching(usize),
+    /// MCTS searches every move, alpha-beta takes over if the move MCTS found got less than
+    /// this share of the root visits
+    Confidence(f32),
+    /// both engines search every move. If they disagree the MCTS move is played if it got at
+    /// least this share of the root visits, the alpha-beta move otherwise
+    Agreement(f32),
+}
+
+/// The switch the hybrid players always used
+pub const DEFAULT_POLICY: SwitchPolicy = SwitchPolicy::Turn(20);
+
+impl SwitchPolicy {
+    /// Reads a policy written as `<kind>=<value>`, e.g. `turn=20` or `confidence=0.6`
+    pub fn from_string(text: &str) -> Result<SwitchPolicy, String> {
+        let mut parts = text.splitn(2, '=');
+        let kind = parts.next().unwrap_or("");
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("Policy {} has no value", text))?;
+        let number_error = || format!("{} is no valid value for {}", value, kind);
+        return match kind {
+            "turn" => Ok(SwitchPolicy::Turn(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            "fishes" => Ok(SwitchPolicy::Fishes(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            "branching" => Ok(SwitchPolicy::Branching(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            "confidence" => Ok(SwitchPolicy::Confidence(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            "agreement" => Ok(SwitchPolicy::Agreement(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            _ => Err(format!("Unknown policy {}", kind)),
+        };
+    }
+
+    /// Move of the hybrid in `state`. `mcts` searches with MCTS and returns the move with the
+    /// share of root visits it got, `alpha_beta` searches with alpha-beta
+    pub fn choose<M, A>(&self, state: &GameState, mut mcts: M, mut alpha_beta: A) -> Move
+    where
+        M: FnMut() -> (Move, f32),
+        A: FnMut() -> Move,
+    {
+        let use_alpha_beta = match *self {
+            SwitchPolicy::Turn(turn) => state.turn >= turn,
+            SwitchPolicy::Fishes(fishes) => {
+                let color = state.get_current_player_color();
+                state.board.get_fields_of(&color).count_ones() <= fishes
+            }
+            SwitchPolicy::Branching(moves) => state.get_move_list().len() <= moves,
+            SwitchPolicy::Confidence(threshold) => {
+                let (action, confidence) = mcts();
+                if confidence >= threshold {
+                    return action;
+                }
+                true
+            }
+            SwitchPolicy::Agreement(threshold) => {
+                let (action, confidence) = mcts();
+                let other = alpha_beta();
+                if action == other || confidence >= threshold {
+                    return action;
+                }
+                return other;
+            }
+        };
+        if use_alpha_beta {
+            return alpha_beta();
+        }
+        return mcts().0;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::gamerules;
+
+    #[test]
+    fn parses_policies() {
+        assert_eq!(SwitchPolicy::from_string("turn=20"), Ok(DEFAULT_POLICY));
+        assert_eq!(
+            SwitchPolicy::from_string("confidence=0.5"),
+            Ok(SwitchPolicy::Confidence(0.5))
+        );
+        assert!(SwitchPolicy::from_string("turn").is_err());
+        assert!(SwitchPolicy::from_string("fishes=many").is_err());
+        assert!(SwitchPolicy::from_string("depth=3").is_err());
+    }
+
+    #[test]
+    fn asks_the_right_engine() {
+        let state = gamerules::get_seeded_state(1);
+        let moves = state.get_move_list();
+        let (first, second) = (moves[0], moves[1]);
+        let choose = |policy: SwitchPolicy, confidence: f32| {
+            return policy.choose(&state, || (first, confidence), || second);
+        };
+        assert_eq!(choose(SwitchPolicy::Turn(20), 1.), first);
+        assert_eq!(choose(SwitchPolicy::Turn(0), 1.), second);
+        assert_eq!(choose(SwitchPolicy::Fishes(16), 1.), second);
+        assert_eq!(choose(SwitchPolicy::Confidence(0.5), 0.6), first);
+        assert_eq!(choose(SwitchPolicy::Confidence(0.5), 0.4), second);
+        assert_eq!(choose(SwitchPolicy::Agreement(0.5), 0.4), second);
+        assert_eq!(choose(SwitchPolicy::Agreement(0.5), 0.6), first);
+    }
+}
@@ -1,16 +1,19 @@
 use game_sdk::{gamerules, GameState, Move, PlayerColor};
 
-use super::evaluation::clop_state as eval_state;
-use super::transposition::{EntryType, MinimalState, TranspositionTable};
+use crate::eval::Evaluator;
+use crate::limit::SearchClock;
+use crate::search::quiescence::{Quiescence, DEFAULT_QUIESCENCE};
+use crate::search::transposition::{EntryType, MinimalState, TranspositionTable};
 
 use std::f32;
 
 pub static MATE_SCORE: f32 = 200_000.0;
 pub static MAX_MATE_PENALTY: f32 = 32.;
-static FUTILITY_MARGIN: f32 = 38.641;
 
 pub struct SearchStatistics {
     pub nodes: usize,
+    /// nodes visited by the quiescence search, not included in `nodes`
+    pub q_nodes: usize,
     pub re_searched: usize,
     pub probed: usize,
     pub aspire_probed: usize,
@@ -21,6 +24,7 @@ impl SearchStatistics {
     pub fn new() -> SearchStatistics {
         return SearchStatistics {
             nodes: 0,
+            q_nodes: 0,
             re_searched: 0,
             probed: 0,
             aspire_probed: 0,
@@ -79,7 +83,8 @@ pub fn minimax_rate(
     beta: f32,
     depth: u8,
     tt: &mut TranspositionTable,
-    start_time: &time::Tm,
+    clock: &SearchClock,
+    evaluator: &dyn Evaluator,
 ) -> f32 {
     let mut state = state.clone();
     let color = state.get_current_player_color();
@@ -92,41 +97,11 @@ pub fn minimax_rate(
         beta,
         depth,
         tt,
-        start_time,
+        clock,
+        evaluator,
     );
 }
 
-#[allow(dead_code)]
-pub fn q_search(state: &GameState, mut alpha: f32, beta: f32, player_index: i8) -> f32 {
-    let color = state.get_current_player_color();
-    let standing_pat = player_index as f32 * eval_state(state);
-    if standing_pat + FUTILITY_MARGIN < alpha {
-        return standing_pat;
-    }
-    alpha = f32::max(alpha, standing_pat);
-    let mut best = standing_pat;
-    if alpha > beta {
-        return alpha;
-    }
-    for action in state.get_captures() {
-        let mut state = state.clone();
-        state.perform(&action, &color);
-        let rate;
-        if gamerules::is_finished(&state) {
-            rate = 0.9 * rate_mate(&state) * player_index as f32;
-        } else {
-            rate = -q_search(&state, -beta, -alpha, -player_index);
-        }
-        alpha = f32::max(alpha, rate);
-        best = f32::max(best, rate);
-        if alpha > beta {
-            return alpha;
-        }
-    }
-    // return alpha;
-    return best;
-}
-
 pub fn minimax_rate_state(
     stats: &mut SearchStatistics,
     state: &GameState,
@@ -135,7 +110,8 @@ pub fn minimax_rate_state(
     mut beta: f32,
     depth: u8,
     tt: &mut TranspositionTable,
-    start_time: &time::Tm,
+    clock: &SearchClock,
+    evaluator: &dyn Evaluator,
 ) -> f32 {
     stats.nodes += 1;
     let hash = MinimalState::from_state(&state);
@@ -160,14 +136,18 @@ pub fn minimax_rate_state(
         return rate_mate(state) * player_index as f32;
     }
 
-    /*if depth == 0 {
-        return player_index as f32 * eval_state(state);
-    }*/
     if depth == 0 {
-        return q_search(state, alpha, beta, player_index);
+        let mut quiescence = Quiescence::new(
+            &DEFAULT_QUIESCENCE,
+            |state: &GameState| evaluator.evaluate(state),
+            |state: &GameState| 0.9 * rate_mate(state),
+        );
+        let rate = quiescence.search(state, alpha, beta, player_index);
+        stats.q_nodes += quiescence.nodes;
+        return rate;
     }
 
-    if depth > 2 && (time::now() - *start_time).num_milliseconds() > 1700 {
+    if depth > 2 && clock.expired(stats.nodes) {
         return f32::NAN;
     }
     moves = state.get_move_list();
@@ -195,7 +175,8 @@ pub fn minimax_rate_state(
         -alpha,
         depth - 1,
         tt,
-        start_time,
+        clock,
+        evaluator,
     );
     if best.is_nan() {
         return f32::NAN;
@@ -220,7 +201,8 @@ pub fn minimax_rate_state(
             -alpha,
             depth - 1,
             tt,
-            start_time,
+            clock,
+            evaluator,
         );
         if rate.is_nan() {
             return f32::NAN;
@@ -234,7 +216,8 @@ pub fn minimax_rate_state(
                 -alpha,
                 depth - 1,
                 tt,
-                start_time,
+                clock,
+                evaluator,
             );
             if rate.is_nan() {
                 return f32::NAN;
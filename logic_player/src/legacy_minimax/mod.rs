@@ -1,5 +1,4 @@
 mod player;
-mod transposition;
 mod evaluation;
 mod algorithm;
 
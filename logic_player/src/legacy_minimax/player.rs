@@ -1,12 +1,15 @@
 use game_sdk::{ClientListener, GameState, Move, PlayerColor};
 
-use crate::util::Helper;
+use crate::eval::{Evaluator, FeatureVector};
+use crate::limit::{SearchClock, SearchLimit, DEFAULT_LIMIT};
+use crate::minimax::evaluation::ClopEvaluator;
 use super::algorithm::{minimax_rate_state, SearchStatistics, MATE_SCORE, MAX_MATE_PENALTY};
-use super::transposition::TranspositionTable;
+use crate::search::report::{line_after, rated_root_moves, ratio};
+use crate::search::transposition::{TranspositionTable, DEFAULT_SIZE_MB};
 
-use game_sdk::logging::{Data, MoveValuePair, State};
+use game_sdk::logging::{send_report, Data, MoveValuePair, SearchReport, State};
 use std::f32;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 use time;
 
 static START_DEPTH: u8 = 0;
@@ -17,21 +20,42 @@ pub struct MinimaxPlayer {
 	id: i64,
 	tt: TranspositionTable,
 	tx: Option<mpsc::Sender<Data>>,
+	limit: SearchLimit,
+	evaluator: Arc<dyn Evaluator>,
 }
 impl MinimaxPlayer {
 	pub fn new(tx: Option<mpsc::Sender<Data>>, id: i64) -> MinimaxPlayer {
 		return MinimaxPlayer {
 			id,
-			tt: TranspositionTable::new(),
+			tt: TranspositionTable::new(DEFAULT_SIZE_MB),
 			tx,
+			limit: DEFAULT_LIMIT,
+			evaluator: Arc::new(ClopEvaluator),
 		};
 	}
+
+	/// Ends every search at `limit`, see `MinimaxPlayer::set_limit`. The player makes no random
+	/// choices, so a node limit alone makes it repeat its moves
+	pub fn set_limit(&mut self, limit: SearchLimit) {
+		self.limit = limit;
+	}
+
+	/// Replaces the evaluation the player searches with, `ClopEvaluator` by default
+	pub fn set_evaluator(&mut self, evaluator: Arc<dyn Evaluator>) {
+		self.evaluator = evaluator;
+	}
 }
 
 impl MinimaxPlayer {
 	pub fn move_with_id(&mut self, state: &GameState, id: i64) -> Move {
-		// self.tt = TranspositionTable::new();
-		let before = time::now();
+		return self.move_since(state, id, time::now());
+	}
+
+	/// Like `move_with_id` for a move request that arrived at `start`, the time spent on it
+	/// before counts against the limit
+	pub fn move_since(&mut self, state: &GameState, id: i64, start: time::Tm) -> Move {
+		let clock = SearchClock::started_at(start, self.limit);
+		self.tt.new_search();
 		let player_index = match state.get_current_player_color() {
 			PlayerColor::Red => 1,
 			PlayerColor::Blue => -1,
@@ -39,6 +63,8 @@ impl MinimaxPlayer {
 
 		let mut playable_moves = state.get_move_list();
 		let mut action = None;
+		let mut rated_moves = Vec::new();
+		let mut current_rated_moves = Vec::new();
 		let mut best = -MATE_SCORE - MAX_MATE_PENALTY;
 
 		let mut current_index = 0;
@@ -48,7 +74,7 @@ impl MinimaxPlayer {
 		let color = state.get_current_player_color();
 		let mut search_stats = SearchStatistics::new();
 
-		while (time::now() - before).num_milliseconds() < 1700 {
+		while !clock.expired(search_stats.nodes) {
 			search_stats.nodes += 1;
 			if current_depth == START_DEPTH {
 				let action_considered = playable_moves
@@ -64,7 +90,8 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth,
 					&mut self.tt,
-					&before,
+					&clock,
+					&*self.evaluator,
 				);
 				if rate.is_nan() {
 					break;
@@ -74,6 +101,7 @@ impl MinimaxPlayer {
 					current_depth_best = rate;
 					current_depth_best_move = Some(action_considered.clone());
 				}
+				current_rated_moves.push((action_considered.clone(), rate));
 			} else if current_index == 0 {
 				let action_considered = playable_moves
 					.get(current_index)
@@ -88,12 +116,14 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
+					&clock,
+					&*self.evaluator,
 				);
 				if current_depth_best.is_nan() {
 					break;
 				}
 				current_depth_best_move = Some(action_considered.clone());
+				current_rated_moves.push((action_considered.clone(), current_depth_best));
 			/*let mut rate = -minimax_rate_state(
 				&mut search_stats,
 				&state,
@@ -102,7 +132,8 @@ impl MinimaxPlayer {
 				-best + WINDOW_SIZE,
 				current_depth,
 				&mut self.tt,
-				&before,
+				&clock,
+				&*self.evaluator,
 			);
 			if rate == f32::NAN {
 				break;
@@ -118,7 +149,8 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
+					&clock,
+					&*self.evaluator,
 				);
 				if rate == f32::NAN {
 					break;
@@ -141,7 +173,8 @@ impl MinimaxPlayer {
 					-current_depth_best,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
+					&clock,
+					&*self.evaluator,
 				);
 				if rate == f32::NAN {
 					break;
@@ -157,7 +190,8 @@ impl MinimaxPlayer {
 						MATE_SCORE + MAX_MATE_PENALTY,
 						current_depth - 1,
 						&mut self.tt,
-						&before,
+						&clock,
+						&*self.evaluator,
 					);
 					if rate == f32::NAN {
 						break;
@@ -167,6 +201,7 @@ impl MinimaxPlayer {
 						current_depth_best_move = Some(action_considered.clone());
 					}
 				}
+				current_rated_moves.push((action_considered.clone(), rate));
 			}
 
 			current_index += 1;
@@ -185,6 +220,8 @@ impl MinimaxPlayer {
 
 				current_depth_best = -MATE_SCORE - MAX_MATE_PENALTY;
 				current_depth_best_move = None;
+				rated_moves = current_rated_moves;
+				current_rated_moves = Vec::new();
 				if (best <= -MATE_SCORE || best >= MATE_SCORE) && action != None {
 					break;
 				}
@@ -193,6 +230,8 @@ impl MinimaxPlayer {
 		if current_depth_best_move != action {
 			if current_depth_best > -MATE_SCORE {
 				action = current_depth_best_move;
+				best = current_depth_best;
+				rated_moves = current_rated_moves;
 				current_depth += 1;
 			}
 		}
@@ -201,23 +240,52 @@ impl MinimaxPlayer {
 			id: id as u32,
 			gamestate: state.clone(),
 			moves,
-			data: MinimaxPlayer::get_data_vec(&state),
+			data: FeatureVector::data_vec(&state),
 		};
 		if let Some(ref tx) = self.tx {
 			tx.send(Data::Step(send_state)).unwrap();
-		} else if id < 0 {
-			let ms_used = (time::now() - before).num_milliseconds();
-			println!(
-				"|{}| {}ms | {} nodes | val {:.3} | re-rate {:.5}/{:.2} | depth {} | {:.0} nps",
-				state.turn,
-				ms_used,
-				search_stats.nodes,
-				best,
-				search_stats.re_searched as f32 / search_stats.probed as f32,
-				search_stats.aspire_re as f32 / search_stats.aspire_probed as f32,
-				current_depth - 1,
-				search_stats.nodes as f32 / (ms_used as f32 / 1000.),
-			);
+		}
+		if self.tx.is_some() || id < 0 {
+			let ms_used = clock.elapsed_ms();
+			let stats = vec![
+				("q_nodes".to_string(), search_stats.q_nodes as f32),
+				(
+					"re_searched".to_string(),
+					ratio(search_stats.re_searched as f32, search_stats.probed as f32),
+				),
+				(
+					"aspiration_re_searched".to_string(),
+					ratio(
+						search_stats.aspire_re as f32,
+						search_stats.aspire_probed as f32,
+					),
+				),
+				(
+					"nps".to_string(),
+					ratio(search_stats.nodes as f32, ms_used as f32 / 1000.),
+				),
+				("tt_hit_rate".to_string(), self.tt.stats.hit_rate()),
+				(
+					"tt_collision_rate".to_string(),
+					self.tt.stats.collision_rate(),
+				),
+			];
+			let action = action.or(current_depth_best_move);
+			let report = SearchReport {
+				engine: "legacy_minimax".to_string(),
+				id,
+				turn: state.turn,
+				time_ms: ms_used,
+				action,
+				score: best,
+				depth: current_depth.checked_sub(1),
+				nodes: search_stats.nodes as u64,
+				proven: None,
+				pv: action.map_or(Vec::new(), |action| line_after(&self.tt, state, action)),
+				moves: rated_root_moves(&self.tt, state, &rated_moves),
+				stats,
+			};
+			send_report(&self.tx, report);
 		}
 		if let Some(action) = action {
 			return action;
@@ -230,8 +298,8 @@ impl MinimaxPlayer {
 	}
 
 	pub fn get_move_and_rate(state: &GameState, max_depth: u8) -> (Move, f32, Vec<(Move, f32)>) {
-		let mut tt = TranspositionTable::new();
-		let before = time::now();
+		let mut tt = TranspositionTable::new(DEFAULT_SIZE_MB);
+		let clock = SearchClock::new(DEFAULT_LIMIT);
 		let player_index = match state.get_current_player_color() {
 			PlayerColor::Red => 1,
 			PlayerColor::Blue => -1,
@@ -266,7 +334,8 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth,
 					&mut tt,
-					&before,
+					&clock,
+					&ClopEvaluator,
 				);
 				if rate == f32::NAN {
 					break;
@@ -291,7 +360,8 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth - 1,
 					&mut tt,
-					&before,
+					&clock,
+					&ClopEvaluator,
 				);
 				if current_depth_best == f32::NAN {
 					break;
@@ -306,7 +376,8 @@ impl MinimaxPlayer {
 				-best + WINDOW_SIZE,
 				current_depth,
 				&mut self.tt,
-				&before,
+				&clock,
+				&ClopEvaluator,
 			);
 			if rate == f32::NAN {
 				break;
@@ -322,7 +393,8 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
+					&clock,
+					&ClopEvaluator,
 				);
 				if rate == f32::NAN {
 					break;
@@ -345,7 +417,8 @@ impl MinimaxPlayer {
 					-current_depth_best,
 					current_depth - 1,
 					&mut tt,
-					&before,
+					&clock,
+					&ClopEvaluator,
 				);
 				if rate.is_nan() {
 					break;
@@ -361,7 +434,8 @@ impl MinimaxPlayer {
 						MATE_SCORE + MAX_MATE_PENALTY,
 						current_depth - 1,
 						&mut tt,
-						&before,
+						&clock,
+						&ClopEvaluator,
 					);
 					if rate.is_nan() {
 						break;
@@ -414,71 +488,6 @@ impl MinimaxPlayer {
 			panic!("No playable move found");
 		}
 	}
-
-	fn get_data_vec(state: &GameState) -> Vec<(String, f32)> {
-		let mut result = MinimaxPlayer::get_colored_data(state, &PlayerColor::Red);
-		result.extend(MinimaxPlayer::get_colored_data(state, &PlayerColor::Blue));
-		return result;
-	}
-
-	fn add_to_result(
-		result: &mut Vec<(String, f32)>,
-		color: &PlayerColor,
-		value: f32,
-		description: &str,
-	) {
-		let string = format!("{}-{}", color, description).to_string();
-		result.push((string, value));
-	}
-
-	fn get_colored_data(state: &GameState, color: &PlayerColor) -> Vec<(String, f32)> {
-		let mut result = Vec::new();
-		let swarm = Helper::greatest_swarm_new(state, color);
-		let mut win: f32 = 0.;
-		let mut len = 0;
-		for (x, y) in state.get_own_fields(color) {
-			win += 1. - (Helper::get_distance_to_swarm_new(x, y, &swarm) as f32 / 9.);
-			len += 1;
-		}
-		MinimaxPlayer::add_to_result(&mut result, color, win, "DISTANCES");
-		win = win / len as f32;
-		MinimaxPlayer::add_to_result(&mut result, color, win, "ADJ_DISTANCES");
-		MinimaxPlayer::add_to_result(&mut result, color, swarm.count_ones() as f32, "SWARM_COUNT");
-		MinimaxPlayer::add_to_result(
-			&mut result,
-			color,
-			swarm.count_ones() as f32 / 16.,
-			"ADJ_SWARM_COUNT",
-		);
-
-		let fishes = state.board.get_fields_of(color);
-
-		let mut center = fishes;
-		center.mask(297799908644072875622400);
-		let center_count = center.count_ones() as f32;
-		MinimaxPlayer::add_to_result(&mut result, color, center_count, "CENTER_COUNT");
-		MinimaxPlayer::add_to_result(
-			&mut result,
-			color,
-			center_count / len as f32,
-			"ADJ_CENTER_COUNT",
-		);
-
-		let mut border = fishes;
-		border.mask(1267033445369934637136782821375);
-		let border_count = border.count_ones() as f32;
-		MinimaxPlayer::add_to_result(&mut result, color, border_count, "BORDER_COUNT");
-		MinimaxPlayer::add_to_result(
-			&mut result,
-			color,
-			border_count / len as f32,
-			"ADJ_BORDER_COUNT",
-		);
-
-		MinimaxPlayer::add_to_result(&mut result, color, len as f32, "COUNT");
-		MinimaxPlayer::add_to_result(&mut result, color, len as f32 / 16., "ADJ_COUNT");
-		return result;
-	}
 }
 
 impl ClientListener for MinimaxPlayer {
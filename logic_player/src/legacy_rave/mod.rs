@@ -1,6 +1,3 @@
-mod mcts;
-
-mod piranhas;
 mod player;
 
 pub use self::player::RavePlayer as LegacyRavePlayer;
\ No newline at end of file
@@ -1,41 +1,104 @@
-use super::mcts::MCTS;
-use super::piranhas::Piranhas;
 use game_sdk::gamerules;
-use game_sdk::logging::{Data, MoveValuePair, State};
+use game_sdk::logging::{send_report, Data, MoveValuePair, State};
 use game_sdk::ClientListener;
 use game_sdk::{GameState, Move};
-use std::sync::mpsc;
-
+use std::sync::{mpsc, Arc};
 use time;
 
+use crate::limit::{SearchClock, SearchLimit, RAVE_LIMIT};
+use crate::mcts::{Piranhas, RaveParameters, LEGACY_RAVE, MCTS};
+use crate::playout::{GreedyPlayout, PlayoutPolicy};
+
+/// Searching on the opponent's time only deepens the lines already preferred
+const IDLE_PARAMETERS: RaveParameters = RaveParameters {
+    c: 0.,
+    ..LEGACY_RAVE
+};
+
 #[derive(Clone)]
 pub struct RavePlayer {
     tx: Option<mpsc::Sender<Data>>,
     id: i64,
     mcts: Option<MCTS>,
+    confidence: f32,
+    playout: Arc<dyn PlayoutPolicy>,
+    limit: SearchLimit,
+    seed: Option<u64>,
 }
 
 impl RavePlayer {
     pub fn new(tx: Option<mpsc::Sender<Data>>, id: i64) -> RavePlayer {
-        return RavePlayer { tx, id, mcts: None };
+        return RavePlayer {
+            tx,
+            id,
+            mcts: None,
+            confidence: 0.,
+            playout: Arc::new(GreedyPlayout),
+            limit: RAVE_LIMIT,
+            seed: None,
+        };
+    }
+
+    /// Ends every search at `limit`, see `RavePlayer::set_limit`
+    pub fn set_limit(&mut self, limit: SearchLimit) {
+        self.limit = limit;
+    }
+
+    /// Draws the random playout moves from a generator seeded with `seed`
+    pub fn set_seed(&mut self, seed: u64) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_seed(seed);
+        }
+        self.seed = Some(seed);
+    }
+
+    /// Plays the games of new nodes out with `policy` instead of the greedy playout
+    pub fn set_playout(&mut self, policy: Arc<dyn PlayoutPolicy>) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_playout(policy.clone());
+        }
+        self.playout = policy;
+    }
+
+    fn new_mcts(&self, game: &Piranhas) -> MCTS {
+        let mut mcts = MCTS::new(game, LEGACY_RAVE);
+        mcts.set_playout(self.playout.clone());
+        if let Some(seed) = self.seed {
+            mcts.set_seed(seed);
+        }
+        return mcts;
+    }
+
+    /// Share of the root visits the last move found by the search got
+    pub fn confidence(&self) -> f32 {
+        return self.confidence;
     }
 
     pub fn move_with_id(&mut self, state: &GameState, id: i64) -> Move {
-        let before = time::now();
+        return self.move_since(state, id, time::now());
+    }
+
+    /// Like `move_with_id` for a move request that arrived at `start`, the time spent on it
+    /// before counts against the limit
+    pub fn move_since(&mut self, state: &GameState, id: i64, start: time::Tm) -> Move {
+        let clock = SearchClock::started_at(start, self.limit);
         let game = Piranhas::from_state(state);
         if let Some(ref mut mcts) = self.mcts {
             mcts.set_root(&game);
         // *mcts = MCTS::new(&game); // to deactivate taking knowledge over from last turn
         } else {
-            self.mcts = Some(MCTS::new(&game));
+            self.mcts = Some(self.new_mcts(&game));
         }
         if let Some(ref mut mcts) = self.mcts {
             let before_samples = mcts.get_root_samples();
-            let c = 0.038;
-            // mcts.search(1000, c);
-            let budget_seconds = 0.1 - ((time::now() - before).num_milliseconds() as f32 / 1000.);
-            mcts.search_time(budget_seconds, c);
-            if let (Some(action), value, depth) = mcts.best_action() {
+            // mcts.search(1000);
+            mcts.search_until(&clock);
+            if let (Some(action), _, _) = mcts.best_action() {
+                self.confidence = mcts
+                    .get_pairs()
+                    .iter()
+                    .find(|pair| pair.1 == action)
+                    .map_or(0., |pair| pair.0);
                 if let Some(ref tx) = self.tx {
                     let moves = mcts
                         .get_pairs()
@@ -52,24 +115,13 @@ impl RavePlayer {
                         data: Vec::new(),
                     };
                     tx.send(Data::Step(send_state)).unwrap();
-                } else if id < 0 {
-                    let stats = mcts.tree_statistics();
-                    if let Some(depth) = depth {
-                        print!("end in {}; ", depth);
-                    }
-                    println!(
-                        "|{}| reused {} samples; {}ms | {} nodes | {}-{} depth | val {:.3} | {} table|{:.0}it/s)",
-                        state.turn,
-                        before_samples,
-                        (time::now() - before).num_milliseconds(),
-                        stats.nodes,
-                        stats.min_depth - 1,
-                        stats.max_depth - 1,
-                        value,
-                        mcts.table_size(),
-                        mcts.iterations_per_s,
-                    );
-                    // println!("{}", action);
+                }
+                if self.tx.is_some() || id < 0 {
+                    let mut report = mcts.report("legacy_rave", id, clock.elapsed_ms());
+                    report
+                        .stats
+                        .push(("reused_samples".to_string(), before_samples));
+                    send_report(&self.tx, report);
                 }
                 return action;
             } else {
@@ -98,13 +150,21 @@ impl ClientListener for RavePlayer {
         if let Some(ref mut mcts) = self.mcts {
             mcts.set_root(&game);
         } else {
-            self.mcts = Some(MCTS::new(&game));
+            self.mcts = Some(self.new_mcts(&game));
+        }
+    }
+
+    fn ponders(&self) -> bool {
+        // the extra iterations would make node-limited searches depend on the opponent's time
+        if let SearchLimit::Nodes(_) = self.limit {
+            return false;
         }
+        return self.mcts.is_some();
     }
 
     fn on_idle(&mut self) {
         if let Some(ref mut mcts) = self.mcts {
-            mcts.search_time(0.001, 0.0);
+            mcts.search_time_with(0.001, IDLE_PARAMETERS);
         }
     }
 }
@@ -2,24 +2,49 @@ extern crate game_sdk;
 extern crate hashbrown;
 extern crate logger;
 extern crate rand;
+extern crate serde;
+#[macro_use]
+extern crate serde_derive;
+extern crate serde_json;
 extern crate time;
 
+mod book;
 mod distance_player;
+mod eval;
 mod hybrid;
 mod legacy_minimax;
 mod legacy_rave;
+mod limit;
+mod mcts;
 mod mcts_rave;
 mod minimax;
+mod playout;
+mod search;
 mod util;
 
+pub use book::{BookMove, OpeningBook};
 pub use distance_player::MultiDistancePlayer;
 pub use distance_player::SingleDistancePlayer;
+pub use eval::{
+	encode, phase_of_turn, Activation, Evaluator, FeatureVector, Layer, Network, NetworkEvaluator,
+	Term, TexelEvaluator, TexelWeights, DEFAULT_TEXEL, INPUTS, POLICY_OUTPUTS,
+};
 pub use hybrid::HybridPlayer;
 pub use hybrid::LegacyHybridPlayer;
+pub use hybrid::{SwitchPolicy, DEFAULT_POLICY};
 pub use legacy_minimax::LegacyMinimaxPlayer;
 pub use legacy_rave::LegacyRavePlayer;
+pub use limit::SearchLimit;
+pub use mcts::{
+	ExportNode, FinalMove, PriorSource, RaveParameters, TreeExport, DEFAULT_NODE_BUDGET,
+	DEFAULT_RAVE, LEGACY_RAVE,
+};
 pub use mcts_rave::RavePlayer;
-pub use minimax::MinimaxPlayer;
+pub use minimax::{explain_mate, ClopEvaluator, MinimaxParameters, MinimaxPlayer};
+pub use playout::{
+	playout_from_string, CutoffPlayout, EpsilonGreedyPlayout, GreedyPlayout, PlayoutPolicy,
+	RandomPlayout, SoftmaxPlayout,
+};
 
 use game_sdk::ClientListener;
 use game_sdk::GameState;
@@ -0,0 +1,106 @@
+use rand::rngs::StdRng;
+use rand::{FromEntropy, SeedableRng};
+
+use time;
+
+/// When a search has to return its move
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum SearchLimit {
+    /// wall-clock milliseconds, how the players search in a game
+    Millis(i64),
+    /// nodes for alpha-beta, iterations for MCTS. Independent of the speed of the machine, so
+    /// a seeded engine plays the same moves on every run
+    Nodes(usize),
+}
+
+impl SearchLimit {
+    /// Reads a limit written as `millis=<milliseconds>` or `nodes=<nodes>`
+    pub fn from_string(text: &str) -> Result<SearchLimit, String> {
+        let mut parts = text.splitn(2, '=');
+        let kind = parts.next().unwrap_or("");
+        let value = parts
+            .next()
+            .ok_or_else(|| format!("Limit {} has no value", text))?;
+        let number_error = || format!("{} is no valid value for {}", value, kind);
+        return match kind {
+            "millis" => Ok(SearchLimit::Millis(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            "nodes" => Ok(SearchLimit::Nodes(
+                value.parse().map_err(|_| number_error())?,
+            )),
+            _ => Err(format!("Unknown limit {}", kind)),
+        };
+    }
+}
+
+/// How long the alpha-beta players think about a move, the server allows 2 s including the
+/// network
+pub const DEFAULT_LIMIT: SearchLimit = SearchLimit::Millis(1700);
+/// How long the RAVE players search a move
+pub const RAVE_LIMIT: SearchLimit = SearchLimit::Millis(100);
+
+/// Start and limit of a running search
+#[derive(Clone, Copy)]
+pub struct SearchClock {
+    start: time::Tm,
+    limit: SearchLimit,
+}
+
+impl SearchClock {
+    /// Starts the clock of a search limited by `limit` now
+    pub fn new(limit: SearchLimit) -> SearchClock {
+        return SearchClock::started_at(time::now(), limit);
+    }
+
+    /// The clock of a search limited by `limit` that started at `start`
+    pub fn started_at(start: time::Tm, limit: SearchLimit) -> SearchClock {
+        return SearchClock { start, limit };
+    }
+
+    /// Whether a search that visited `nodes` so far has to stop
+    pub fn expired(&self, nodes: usize) -> bool {
+        return match self.limit {
+            SearchLimit::Millis(millis) => self.elapsed_ms() > millis,
+            SearchLimit::Nodes(max_nodes) => nodes > max_nodes,
+        };
+    }
+
+    pub fn limit(&self) -> SearchLimit {
+        return self.limit;
+    }
+
+    pub fn elapsed_ms(&self) -> i64 {
+        return (time::now() - self.start).num_milliseconds();
+    }
+}
+
+/// Generator of an engine's random choices, seeded by `seed` or by the system without one
+pub fn engine_rng(seed: Option<u64>) -> StdRng {
+    return match seed {
+        Some(seed) => StdRng::seed_from_u64(seed),
+        None => StdRng::from_entropy(),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_limits() {
+        assert_eq!(
+            SearchLimit::from_string("millis=100"),
+            Ok(SearchLimit::Millis(100))
+        );
+        assert_eq!(
+            SearchLimit::from_string("nodes=20000"),
+            Ok(SearchLimit::Nodes(20000))
+        );
+        assert!(SearchLimit::from_string("nodes").is_err());
+        assert!(SearchLimit::from_string("depth=4").is_err());
+        let clock = SearchClock::new(SearchLimit::Nodes(10));
+        assert!(!clock.expired(10));
+        assert!(clock.expired(11));
+    }
+}
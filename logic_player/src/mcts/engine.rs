@@ -0,0 +1,1022 @@
+use super::export::{ExportNode, TreeExport};
+use super::params::{FinalMove, RaveParameters};
+use super::piranhas::{MinimalState, Piranhas};
+use game_sdk::logging::{RootMove, SearchReport, MAX_PV_LENGTH};
+use game_sdk::Move;
+use game_sdk::PlayerColor;
+use hashbrown::{HashMap, HashSet};
+use rand::rngs::StdRng;
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use std::f32;
+use std::i32;
+use std::mem;
+use std::sync::Arc;
+
+use crate::eval::Network;
+use crate::limit::{engine_rng, SearchClock, SearchLimit};
+use crate::mcts::solver::{prove, terminal_outcome, value_of, Outcome};
+use crate::playout::{GreedyPlayout, PlayoutPolicy};
+use crate::LogicBasedPlayer;
+
+/// Plays `initial` to its end, or until `policy` estimates the result, and counts every move
+/// played into `rave_table`. `plies` is the number of moves the playout made so far
+fn playout(
+    initial: &Piranhas,
+    color: &PlayerColor,
+    rave_table: &mut HashMap<Move, Value>,
+    policy: &dyn PlayoutPolicy,
+    plies: u8,
+    rng: &mut StdRng,
+) -> f32 {
+    let mut game = initial.clone();
+    if game.is_finished() {
+        return game.reward(color);
+    }
+    if let Some(chance) = policy.estimate(&game.state, plies, color) {
+        // the same point of view as `reward`
+        return 1. - chance;
+    }
+    if let Some(action) = policy.next_move(&game.state, rng) {
+        game.make_move(&action);
+        let val = playout(&game, color, rave_table, policy, plies + 1, rng);
+        let mut rave = rave_table.remove(&action).unwrap_or(Value::new());
+        rave.n += 1.;
+        rave.q += if initial.get_color() != *color {
+            val
+        } else {
+            1. - val
+        };
+        rave_table.insert(action.clone(), rave);
+        return val;
+    } else {
+        if *color == game.get_color() {
+            return 0.0;
+        }
+        return 1.0;
+    }
+}
+
+#[derive(Copy, Clone, Debug)]
+struct ChildEdge {
+    pub index: MinimalState,
+    pub action: Move,
+    pub added: bool,
+    /// prior probability of the move for PUCT
+    pub prior: f32,
+}
+
+impl ChildEdge {
+    pub fn new(index: MinimalState, action: Move, added: bool, prior: f32) -> ChildEdge {
+        return ChildEdge {
+            index,
+            action,
+            added,
+            prior,
+        };
+    }
+}
+
+/// What the nodes need to know about the search running through them
+#[derive(Clone, Copy)]
+struct Search<'a> {
+    params: &'a RaveParameters,
+    network: Option<&'a Network>,
+    playout: &'a dyn PlayoutPolicy,
+    max_nodes: usize,
+}
+
+#[derive(Clone, Debug, Copy)]
+pub struct Value {
+    pub q: f32,
+    pub n: f32,
+}
+
+impl Value {
+    pub fn new() -> Value {
+        return Value { n: 0., q: 0. };
+    }
+
+    /// Mean result, 0 without samples
+    pub fn mean(&self) -> f32 {
+        if self.n > 0. {
+            return self.q / self.n;
+        }
+        return 0.;
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct TreeNode {
+    index: MinimalState,
+    parents: Vec<MinimalState>,
+    children: Vec<ChildEdge>, // next steps we investigated
+    n: f32,
+    q: f32, // statistics for this game state
+    lower_bound: f32,
+    /// outcome for the side to move once the search proved it
+    proof: Option<Outcome>,
+    stats: Option<(i32, i32, i32)>,
+}
+
+impl TreeNode {
+    /// Create and initialize a new TreeNode
+    pub fn new(index: MinimalState, parent: Option<MinimalState>) -> TreeNode {
+        let parents;
+        if let Some(s) = parent {
+            parents = vec![s];
+        } else {
+            parents = Vec::new();
+        }
+        TreeNode {
+            index,
+            parents,
+            children: Vec::new(),
+            n: 0.,
+            q: 0.,
+            lower_bound: -2.0,
+            proof: None,
+            stats: None,
+        }
+    }
+
+    /// Gather some statistics about this subtree
+    pub fn tree_statistics(
+        &mut self,
+        node_table: &mut HashMap<MinimalState, TreeNode>,
+    ) -> (i32, i32, i32) {
+        if let Some(stats) = self.stats {
+            return stats;
+        }
+        let mut nodes = 1;
+        let mut max_depth = 0;
+        let mut min_depth = 200;
+        for c in &self.children {
+            if let Some(mut child) = node_table.remove(&c.index) {
+                let (n, max, min) = child.tree_statistics(node_table);
+                nodes += n;
+                max_depth = i32::max(max, max_depth);
+                min_depth = i32::min(min, min_depth);
+                node_table.insert(c.index, child);
+            }
+        }
+        if nodes == 1 {
+            return (1, 0, 0);
+        }
+        self.stats = Some((nodes, max_depth + 1, min_depth + 1));
+        return (nodes, max_depth + 1, min_depth + 1);
+    }
+
+    /// Children are sorted by the policy of the network if it has one, otherwise by the
+    /// variance the move leaves. Unvisited children are tried in this order
+    fn add_own_children(&mut self, game: &mut Piranhas, search: Search) -> bool {
+        let mut rated_actions = LogicBasedPlayer::get_rated_moves(&game.state);
+        if rated_actions.is_empty() {
+            self.proof = terminal_outcome(&game.state);
+            return false;
+        }
+        rated_actions.sort_unstable_by_key(|a| a.0);
+        let params = search.params;
+        let priors = if params.puct > 0. {
+            params
+                .prior
+                .priors(&game.state, &rated_actions, params.prior_temperature)
+        } else {
+            vec![0.; rated_actions.len()]
+        };
+        let mut actions: Vec<(Move, f32)> = rated_actions
+            .into_iter()
+            .map(|(_, action)| action)
+            .zip(priors)
+            .collect();
+        if let Some(network) = search.network.filter(|network| network.has_policy()) {
+            let moves: Vec<Move> = actions.iter().map(|&(action, _)| action).collect();
+            let policy = network.policy(&game.state, &moves);
+            let mut order: Vec<usize> = (0..actions.len()).collect();
+            order.sort_by(|&a, &b| policy[b].partial_cmp(&policy[a]).unwrap_or(Ordering::Equal));
+            actions = order.into_iter().map(|index| actions[index]).collect();
+        }
+        for (action, prior) in actions {
+            let mut game_clone = game.clone();
+            game_clone.make_move(&action);
+            let state = MinimalState::from_state(&game_clone.state);
+            self.children
+                .push(ChildEdge::new(state, action, false, prior));
+        }
+        return true;
+    }
+
+    /// Proves this node from the proofs of its children and fixes its value to the outcome
+    fn update_proof(&mut self, node_table: &HashMap<MinimalState, TreeNode>) -> Option<Outcome> {
+        let outcomes = self.children.iter().map(|edge| match edge.added {
+            true => node_table.get(&edge.index).and_then(|node| node.proof),
+            false => None,
+        });
+        let (_, outcome) = prove(outcomes)?;
+        self.proof = Some(outcome);
+        self.q = value_of(outcome) * self.n;
+        return Some(outcome);
+    }
+
+    fn best_child_fpu(
+        &mut self,
+        game: &mut Piranhas,
+        search: Search,
+        node_table: &mut HashMap<MinimalState, TreeNode>,
+        rave_table: &mut HashMap<Move, Value>,
+        rng: &mut StdRng,
+        is_root: bool,
+    ) -> (f32, f32) {
+        let color = game.get_color();
+        // child generation
+        if self.children.len() == 0 {
+            if !self.add_own_children(game, search) {
+                return (game.reward(&color), 1.0);
+            }
+        }
+
+        let mut n = 0.0;
+        let mut q = 0.0;
+
+        let params = search.params;
+        let fpu_base = if is_root {
+            1.5
+        } else {
+            (self.n - self.q) / self.n - params.fpu_r
+        };
+        let fpu_exploration = (self.n.ln() / 1.).sqrt();
+        let mut best_value: f32 = f32::NEG_INFINITY;
+        let mut best_child_index: Option<usize> = None;
+        let c_base = params.c_base as f32;
+        let b_squared = params.b_squared;
+        let c = params.c + params.c_factor * ((1. + self.n + c_base) / c_base).ln();
+        let sqrt_n = self.n.sqrt();
+        let mut proven = 0;
+        // children past the width are left out until the node got more visits
+        let width = params.width(self.n);
+        let mut searched = 0;
+        for (idx, edge) in self.children.iter_mut().enumerate() {
+            if searched >= width {
+                break;
+            }
+            if !edge.added {
+                if let Some(node) = node_table.get_mut(&edge.index) {
+                    node.add_parent(self.index);
+                    n += node.n;
+                    q += node.n - node.q;
+                    edge.added = true;
+                } else {
+                    let rave = rave_table.remove(&edge.action).unwrap_or(Value::new());
+                    let beta =
+                        f32::min(rave.n / (rave.n + 10. + 4. * b_squared * rave.n * 10.), 1.0);
+                    let exploration = if params.puct > 0. {
+                        params.puct * edge.prior * sqrt_n
+                    } else {
+                        c * fpu_exploration
+                    };
+                    let value = (1. - beta) * fpu_base + exploration + beta * (rave.q / rave.n);
+                    if value > best_value || best_child_index == None {
+                        best_child_index = Some(idx);
+                        best_value = value;
+                    }
+                    rave_table.insert(edge.action.clone(), rave);
+                    searched += 1;
+                    continue;
+                }
+            }
+            let node = node_table
+                .get(&edge.index)
+                .expect("ERROR: Did not find child in UCT");
+            if node.proof.is_some() {
+                // proven children are not searched again, they only prove this node
+                self.lower_bound = f32::max(self.lower_bound, node.q / node.n);
+                proven += 1;
+            } else {
+                searched += 1;
+                let rave = rave_table.remove(&edge.action).unwrap_or(Value::new());
+                let beta = f32::min(
+                    rave.n / (rave.n + node.n + 4. * b_squared * rave.n * node.n),
+                    1.0,
+                );
+                let exploration = if params.puct > 0. {
+                    params.puct * edge.prior * sqrt_n / (1. + node.n)
+                } else {
+                    c * (2. * self.n.ln() / node.n).sqrt()
+                };
+                let value =
+                    (1. - beta) * (node.q / node.n) + exploration + beta * (rave.q / rave.n);
+                if value > best_value || best_child_index == None {
+                    best_value = value;
+                    best_child_index = Some(idx);
+                }
+                rave_table.insert(edge.action.clone(), rave);
+            }
+        }
+        if proven > 0 && self.update_proof(node_table).is_some() {
+            return (self.q / self.n, 1.0);
+        }
+        if let Some(idx) = best_child_index {
+            let edge = self
+                .children
+                .get_mut(idx)
+                .expect("Should never happen, index is from iteration");
+            if !edge.added {
+                game.make_move(&edge.action);
+                let delta = playout(game, &game.get_color(), rave_table, search.playout, 0, rng);
+                n += 1.0;
+                q += 1.0 - delta;
+                // with a full table the edge stays unexpanded, its playouts still count here
+                if node_table.len() < search.max_nodes {
+                    edge.added = true;
+                    let mut node = TreeNode::new(edge.index.clone(), Some(self.index));
+                    node.backpropagate(delta, 1.0, node_table);
+                    node_table.insert(edge.index.clone(), node);
+                }
+                if let Some(val) = rave_table.get_mut(&edge.action) {
+                    val.n += 1.;
+                    val.q += delta;
+                } else {
+                    let mut val = Value::new();
+                    val.q = delta;
+                    val.n = 1.;
+                    rave_table.insert(edge.action.clone(), val);
+                }
+                return (q, n);
+            }
+            let mut child = node_table
+                .remove(&edge.index)
+                .expect("ERROR: Did not find child in iteration");
+            game.make_move(&edge.action);
+            let (delta, delta_n) =
+                child.iteration(game, search, node_table, rave_table, rng, false);
+            q += delta_n - delta;
+            n += delta_n;
+            node_table.insert(edge.index, child);
+            if let Some(val) = rave_table.get_mut(&edge.action) {
+                val.n += delta_n;
+                val.q += delta;
+            } else {
+                let mut val = Value::new();
+                val.q = delta;
+                val.n = delta_n;
+                rave_table.insert(edge.action.clone(), val);
+            }
+            return (q, n);
+        } else {
+            unreachable!("a node that is neither terminal nor proven has a child to select");
+        }
+    }
+
+    pub fn add_parent(&mut self, index: MinimalState) {
+        self.parents.push(index);
+    }
+
+    pub fn backpropagate(
+        &mut self,
+        q: f32,
+        n: f32,
+        node_table: &mut HashMap<MinimalState, TreeNode>,
+    ) {
+        self.stats = None;
+        let value = q / n;
+        let value = f32::min(1. - self.lower_bound, value);
+        self.q += value * n;
+        self.n += n;
+        for parent in &self.parents {
+            if let Some(mut node) = node_table.remove(parent) {
+                node.backpropagate(n - q, n, node_table);
+                node_table.insert(parent.clone(), node);
+            }
+        }
+    }
+
+    /// Recursively perform an MCTS iteration.
+    fn iteration(
+        &mut self,
+        game: &mut Piranhas,
+        search: Search,
+        node_table: &mut HashMap<MinimalState, TreeNode>,
+        rave_table: &mut HashMap<Move, Value>,
+        rng: &mut StdRng,
+        is_root: bool,
+    ) -> (f32, f32) {
+        self.stats = None;
+        let (delta, n) = match self.proof {
+            Some(_) => (self.q / self.n, 1.0),
+            None => self.best_child_fpu(game, search, node_table, rave_table, rng, is_root),
+        };
+        self.backpropagate(delta, n, node_table);
+        return (delta, n);
+    }
+}
+
+#[derive(Debug, Copy, Clone)]
+/// Store and process some simple statistical information about NodeTrees.
+pub struct TreeStatistics {
+    pub nodes: i32,
+    pub min_depth: i32,
+    pub max_depth: i32,
+    /// nodes in the table, transpositions counted once
+    pub table_size: usize,
+    /// approximate bytes held by the node and RAVE tables
+    pub memory: usize,
+    /// nodes removed when the root was last moved
+    pub pruned: usize,
+}
+
+impl TreeStatistics {
+    pub fn filled(nodes: i32, max_depth: i32, min_depth: i32) -> TreeStatistics {
+        return TreeStatistics {
+            nodes,
+            min_depth,
+            max_depth,
+            table_size: 0,
+            memory: 0,
+            pruned: 0,
+        };
+    }
+}
+
+#[derive(Clone)]
+pub struct MCTS {
+    root: MinimalState,
+    game: Piranhas,
+    pub iterations_per_s: f32,
+    node_table: HashMap<MinimalState, TreeNode>,
+    rave_table: HashMap<Move, Value>,
+    params: RaveParameters,
+    network: Option<Arc<Network>>,
+    playout: Arc<dyn PlayoutPolicy>,
+    max_nodes: usize,
+    pruned: usize,
+    rng: StdRng,
+}
+
+/// Nodes the table may hold unless `MCTS::set_node_budget` says otherwise
+pub const DEFAULT_NODE_BUDGET: usize = 1_000_000;
+
+impl MCTS {
+    /// Create a new MCTS solver.
+    pub fn new(game: &Piranhas, params: RaveParameters) -> MCTS {
+        let mut node_table = HashMap::with_capacity(100_000);
+        let state = MinimalState::from_state(&game.state);
+        node_table.insert(
+            state,
+            TreeNode::new(state.clone(), Some(MinimalState::EMPTY_STATE)),
+        );
+        MCTS {
+            root: state,
+            game: game.clone(),
+            iterations_per_s: 1.,
+            node_table,
+            rave_table: HashMap::with_capacity(100_000),
+            params,
+            playout: Arc::new(GreedyPlayout),
+            network: None,
+            max_nodes: DEFAULT_NODE_BUDGET,
+            pruned: 0,
+            rng: engine_rng(None),
+        }
+    }
+
+    /// Draws the random choices of the playouts from a generator seeded with `seed`. Together
+    /// with `search` instead of `search_time` this makes the search repeatable
+    pub fn set_seed(&mut self, seed: u64) {
+        self.rng = engine_rng(Some(seed));
+    }
+
+    /// Orders the children of new nodes by the policy of `network`
+    pub fn set_network(&mut self, network: Option<Arc<Network>>) {
+        self.network = network;
+    }
+
+    /// Plays the games of new nodes out with `policy`
+    pub fn set_playout(&mut self, policy: Arc<dyn PlayoutPolicy>) {
+        self.playout = policy;
+    }
+
+    /// Stops adding nodes once the table holds `max_nodes`. The nodes an iteration passes are
+    /// out of the table until it ends, so it can exceed the budget by the depth of the tree
+    pub fn set_node_budget(&mut self, max_nodes: usize) {
+        self.max_nodes = max_nodes;
+    }
+
+    #[allow(unused)]
+    pub fn get_root_samples(&self) -> f32 {
+        if let Some(node) = self.node_table.get(&self.root) {
+            return node.n;
+        }
+        return 0.0;
+    }
+
+    #[allow(unused)]
+    pub fn set_root(&mut self, game: &Piranhas) {
+        let state = MinimalState::from_state(&game.state);
+        if state == self.root {
+            return;
+        }
+        self.game = game.clone();
+        if let Some(mut node) = self.node_table.remove(&state) {
+            node.add_parent(MinimalState::EMPTY_STATE);
+            self.root = state;
+            self.node_table.insert(state, node);
+            self.pruned = self.prune();
+        } else {
+            self.pruned = self.node_table.len();
+            self.node_table = HashMap::with_capacity(100_000);
+            self.root = state;
+            self.node_table.insert(
+                state,
+                TreeNode::new(state.clone(), Some(MinimalState::EMPTY_STATE)),
+            );
+        }
+    }
+
+    /// Removes the nodes that can't be reached from the root anymore and the parents they
+    /// leave behind, returns the number of nodes removed
+    fn prune(&mut self) -> usize {
+        let mut reachable = HashSet::with_capacity(self.node_table.len());
+        reachable.insert(self.root);
+        let mut open = vec![self.root];
+        while let Some(index) = open.pop() {
+            if let Some(node) = self.node_table.get(&index) {
+                for edge in node.children.iter().filter(|edge| edge.added) {
+                    if reachable.insert(edge.index) {
+                        open.push(edge.index);
+                    }
+                }
+            }
+        }
+        let before = self.node_table.len();
+        self.node_table.retain(|index, _| reachable.contains(index));
+        for node in self.node_table.values_mut() {
+            node.parents.retain(|parent| {
+                *parent == MinimalState::EMPTY_STATE || reachable.contains(parent)
+            });
+        }
+        return before - self.node_table.len();
+    }
+
+    /// Approximate bytes held by the node and RAVE tables
+    pub fn memory_usage(&self) -> usize {
+        let mut bytes = self.node_table.capacity() * mem::size_of::<(MinimalState, TreeNode)>()
+            + self.rave_table.capacity() * mem::size_of::<(Move, Value)>();
+        for node in self.node_table.values() {
+            bytes += node.children.capacity() * mem::size_of::<ChildEdge>()
+                + node.parents.capacity() * mem::size_of::<MinimalState>();
+        }
+        return bytes;
+    }
+
+    pub fn table_size(&self) -> usize {
+        return self.node_table.len();
+    }
+
+    /// Return basic statistical data about the current MCTS tree.
+    pub fn tree_statistics(&mut self) -> TreeStatistics {
+        let mut root = self
+            .node_table
+            .remove(&self.root)
+            .expect("ERROR: Did not find root for statistics");
+        let (nodes, max_depth, min_depth) = root.tree_statistics(&mut self.node_table);
+        self.node_table.insert(self.root, root);
+        return TreeStatistics {
+            table_size: self.table_size(),
+            memory: self.memory_usage(),
+            pruned: self.pruned,
+            ..TreeStatistics::filled(nodes, max_depth, min_depth)
+        };
+    }
+
+    /// Perform n_samples MCTS iterations.
+    pub fn search(&mut self, n_samples: usize) {
+        let search = Search {
+            params: &self.params,
+            network: self.network.as_deref(),
+            playout: self.playout.as_ref(),
+            max_nodes: self.max_nodes,
+        };
+        for _ in 0..n_samples {
+            let mut root = self
+                .node_table
+                .remove(&self.root)
+                .expect("ERROR: Did not find root in search");
+            let mut this_game = self.game.clone();
+            root.iteration(
+                &mut this_game,
+                search,
+                &mut self.node_table,
+                &mut self.rave_table,
+                &mut self.rng,
+                true,
+            );
+            self.node_table.insert(self.root, root);
+        }
+    }
+
+    /// Perform MCTS iterations for the given time budget (in s).
+    #[allow(unused)]
+    pub fn search_time(&mut self, budget_seconds: f32) {
+        let mut samples_total = 0;
+        let t0 = time::now();
+
+        let mut n_samples = 20;
+        while n_samples > 19 {
+            self.search(n_samples);
+            samples_total += n_samples;
+
+            let time_spend = (time::now() - t0).num_milliseconds() as f32 / 1000.;
+            self.iterations_per_s = samples_total as f32 / time_spend;
+
+            let time_left = budget_seconds - time_spend;
+            n_samples = (self.iterations_per_s * time_left).max(0.).min(20.) as usize;
+        }
+    }
+
+    /// Like `search_time`, but with `params` in place of the parameters of the engine
+    pub fn search_time_with(&mut self, budget_seconds: f32, params: RaveParameters) {
+        let own = mem::replace(&mut self.params, params);
+        self.search_time(budget_seconds);
+        self.params = own;
+    }
+
+    /// Searches until the limit of `clock`: the rest of its time, or its nodes as iterations
+    pub fn search_until(&mut self, clock: &SearchClock) {
+        match clock.limit() {
+            SearchLimit::Millis(millis) => {
+                self.search_time((millis - clock.elapsed_ms()) as f32 / 1000.);
+            }
+            SearchLimit::Nodes(iterations) => self.search(iterations),
+        }
+    }
+
+    /// Return the best action found so far, its value and the outcome of the root once the
+    /// search proved it
+    pub fn best_action(&self) -> (Option<Move>, f32, Option<Outcome>) {
+        let root = self
+            .node_table
+            .get(&self.root)
+            .expect("ERROR: Did not find root for best action");
+        let children: Vec<(Move, Option<&TreeNode>)> = root
+            .children
+            .iter()
+            .map(|c| (c.action, self.node_table.get(&c.index)))
+            .collect();
+        let outcomes = children
+            .iter()
+            .map(|&(_, child)| child.and_then(|child| child.proof));
+        if let Some((index, outcome)) = prove(outcomes) {
+            let (action, child) = children[index];
+            let child = child.expect("ERROR: Did not find proven child");
+            return (Some(action), child.q / child.n, Some(outcome));
+        }
+
+        // Find best action, a move the opponent is proven to win after only if there is no other
+        let mut best_action: Option<Move> = None;
+        let mut best_value: f32 = f32::NEG_INFINITY;
+        let mut lost = None;
+        for (action, child) in children {
+            if let Some(child) = child {
+                if let Some(Outcome::Win(_)) = child.proof {
+                    lost = Some(action);
+                    continue;
+                }
+                let value = match self.params.final_move {
+                    FinalMove::MostVisited => child.n,
+                    FinalMove::BestValue => child.q / child.n,
+                };
+                if value >= best_value || best_action == None {
+                    best_action = Some(action);
+                    best_value = value;
+                }
+            }
+        }
+        return (best_action.or(lost), best_value, None);
+    }
+
+    /// The `max_nodes` most visited nodes reachable from the root. They are taken best first,
+    /// so the parent of every node exported is part of the export too
+    pub fn export(&self, max_nodes: usize) -> TreeExport {
+        let mut nodes: Vec<ExportNode> = Vec::new();
+        let mut exported = HashSet::new();
+        let mut candidates: Vec<(MinimalState, Option<usize>, Option<Move>)> =
+            vec![(self.root, None, None)];
+        let mut open = BinaryHeap::new();
+        open.push((0, 0));
+        while let Some((_, candidate)) = open.pop() {
+            if nodes.len() >= max_nodes {
+                break;
+            }
+            let (index, parent, action) = candidates[candidate];
+            let node = match self.node_table.get(&index) {
+                Some(node) if exported.insert(index) => node,
+                _ => continue,
+            };
+            let rave = action
+                .and_then(|action| self.rave_table.get(&action).cloned())
+                .unwrap_or(Value::new());
+            let id = nodes.len();
+            nodes.push(ExportNode {
+                id,
+                parent,
+                action,
+                visits: node.n,
+                q: node.q / f32::max(node.n, 1.),
+                rave_visits: rave.n,
+                rave_q: rave.mean(),
+                proof: node.proof,
+            });
+            for edge in node.children.iter().filter(|edge| edge.added) {
+                if let Some(child) = self.node_table.get(&edge.index) {
+                    candidates.push((edge.index, Some(id), Some(edge.action)));
+                    open.push((child.n as u64, candidates.len() - 1));
+                }
+            }
+        }
+        return TreeExport {
+            turn: self.game.state.turn,
+            nodes,
+        };
+    }
+
+    /// The most visited line from the node `index` on
+    fn principal_variation(&self, mut index: MinimalState) -> Vec<Move> {
+        let mut pv = Vec::new();
+        while pv.len() < MAX_PV_LENGTH {
+            let node = match self.node_table.get(&index) {
+                Some(node) => node,
+                None => break,
+            };
+            let best = node
+                .children
+                .iter()
+                .filter(|edge| edge.added)
+                .filter_map(|edge| {
+                    self.node_table
+                        .get(&edge.index)
+                        .map(|child| (edge, child.n))
+                })
+                .max_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+            match best {
+                Some((edge, _)) => {
+                    pv.push(edge.action);
+                    index = edge.index;
+                }
+                None => break,
+            }
+        }
+        return pv;
+    }
+
+    /// The searched root moves with their visits, win chance and most visited line, the most
+    /// visited first
+    pub fn root_moves(&self) -> Vec<RootMove> {
+        let root = self
+            .node_table
+            .get(&self.root)
+            .expect("ERROR: Did not find root for root moves");
+        let mut moves: Vec<RootMove> = root
+            .children
+            .iter()
+            .filter_map(|edge| {
+                let child = self.node_table.get(&edge.index)?;
+                let mut pv = vec![edge.action];
+                pv.extend(self.principal_variation(edge.index));
+                pv.truncate(MAX_PV_LENGTH);
+                return Some(RootMove {
+                    action: edge.action,
+                    visits: Some(child.n),
+                    score: child.q / f32::max(child.n, 1.),
+                    pv,
+                });
+            })
+            .collect();
+        moves.sort_by(|a, b| b.visits.partial_cmp(&a.visits).unwrap_or(Ordering::Equal));
+        return moves;
+    }
+
+    /// Everything the last search found, `time_ms` being how long it took
+    pub fn report(&mut self, engine: &str, id: i64, time_ms: i64) -> SearchReport {
+        let (action, _, outcome) = self.best_action();
+        let moves = self.root_moves();
+        let stats = self.tree_statistics();
+        let chosen = moves
+            .iter()
+            .find(|root_move| Some(root_move.action) == action);
+        return SearchReport {
+            engine: engine.to_string(),
+            id,
+            turn: self.game.state.turn,
+            time_ms,
+            action,
+            score: chosen.map_or(0., |root_move| root_move.score),
+            depth: None,
+            nodes: stats.nodes as u64,
+            proven: outcome.map(|outcome| format!("{:?}", outcome)),
+            pv: chosen.map_or(Vec::new(), |root_move| root_move.pv.clone()),
+            stats: vec![
+                ("root_samples".to_string(), self.get_root_samples()),
+                ("min_depth".to_string(), stats.min_depth as f32),
+                ("max_depth".to_string(), stats.max_depth as f32),
+                ("table_size".to_string(), stats.table_size as f32),
+                ("pruned".to_string(), stats.pruned as f32),
+                ("memory_kb".to_string(), (stats.memory / 1024) as f32),
+                // infinite after a search that took less than a millisecond
+                (
+                    "iterations_per_s".to_string(),
+                    if self.iterations_per_s.is_finite() {
+                        self.iterations_per_s
+                    } else {
+                        0.
+                    },
+                ),
+            ],
+            moves,
+        };
+    }
+
+    pub fn get_pairs(&self) -> Vec<(f32, Move)> {
+        let mut ret_val = Vec::new();
+
+        let root = self
+            .node_table
+            .get(&self.root)
+            .expect("ERROR: Did not find root for pairs");
+        for c in &root.children {
+            if let Some(child) = self.node_table.get(&c.index) {
+                let value = child.n / root.n;
+                ret_val.push((value, c.action.clone()));
+            }
+        }
+        return ret_val;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mcts::{PriorSource, DEFAULT_RAVE};
+    use crate::playout::RandomPlayout;
+    use game_sdk::{gamerules, Board, FieldType, GameState};
+
+    #[test]
+    fn prunes_and_keeps_to_the_budget() {
+        let mut state = gamerules::get_seeded_state(1);
+        let mut mcts = MCTS::new(&Piranhas::from_state(&state), DEFAULT_RAVE);
+        mcts.set_seed(1);
+        mcts.search(2000);
+        let action = mcts.best_action().0.expect("No move found");
+        let color = state.get_current_player_color();
+        state.perform(&action, &color);
+        let before = mcts.table_size();
+        mcts.set_root(&Piranhas::from_state(&state));
+        let stats = mcts.tree_statistics();
+        assert!(stats.pruned > 0);
+        assert_eq!(stats.table_size + stats.pruned, before);
+        assert!(stats.memory > 0);
+        for node in mcts.node_table.values() {
+            for parent in node.parents.iter() {
+                assert!(
+                    *parent == MinimalState::EMPTY_STATE || mcts.node_table.contains_key(parent)
+                );
+            }
+        }
+
+        mcts.set_node_budget(stats.table_size + 50);
+        mcts.search(2000);
+        // the nodes on the path of an iteration are out of the table while it runs
+        assert!(mcts.table_size() <= stats.table_size + 50 + 60);
+        assert!(mcts.best_action().0.is_some());
+    }
+
+    #[test]
+    fn exports_the_most_visited_nodes() {
+        let state = gamerules::get_seeded_state(2);
+        let mut mcts = MCTS::new(&Piranhas::from_state(&state), DEFAULT_RAVE);
+        mcts.set_seed(2);
+        mcts.search(1000);
+        let tree = mcts.export(50);
+        assert_eq!(tree.nodes.len(), 50);
+        assert_eq!(tree.nodes[0].parent, None);
+        assert_eq!(tree.nodes[0].visits, mcts.get_root_samples());
+        for node in tree.nodes.iter().skip(1) {
+            let parent = node.parent.expect("Node without parent");
+            assert!(parent < node.id);
+            assert!(node.action.is_some());
+        }
+        assert_eq!(tree.to_dot().matches(" -> ").count(), 49);
+        let json: serde_json::Value = serde_json::from_str(&tree.to_json()).expect("Bad JSON");
+        assert_eq!(json["nodes"].as_array().map(|nodes| nodes.len()), Some(50));
+    }
+
+    #[test]
+    fn reports_the_root_moves() {
+        let state = gamerules::get_seeded_state(3);
+        let mut mcts = MCTS::new(&Piranhas::from_state(&state), DEFAULT_RAVE);
+        mcts.set_seed(3);
+        mcts.search(1000);
+        let report = mcts.report("rave", -1, 100);
+        assert_eq!(report.action, mcts.best_action().0);
+        assert_eq!(report.pv.first().cloned(), report.action);
+        assert!(report.pv.len() > 1 && report.pv.len() <= MAX_PV_LENGTH);
+        assert_eq!(report.moves.len(), mcts.get_pairs().len());
+        for pair in report.moves.windows(2) {
+            assert!(pair[0].visits >= pair[1].visits);
+        }
+        for root_move in report.moves.iter() {
+            assert_eq!(root_move.pv[0], root_move.action);
+            assert!(root_move.score.is_finite());
+        }
+        let parsed = SearchReport::from_json(&report.to_json()).expect("Bad JSON");
+        assert_eq!(parsed.pv, report.pv);
+        assert_eq!(parsed.moves.len(), report.moves.len());
+    }
+
+    #[test]
+    fn seeded_searches_repeat() {
+        let state = gamerules::get_seeded_state(3);
+        let searched = || {
+            let mut mcts = MCTS::new(&Piranhas::from_state(&state), DEFAULT_RAVE);
+            mcts.set_playout(Arc::new(RandomPlayout));
+            mcts.set_seed(7);
+            mcts.search(500);
+            return mcts
+                .root_moves()
+                .iter()
+                .map(|root_move| (root_move.action, root_move.visits, root_move.score))
+                .collect::<Vec<_>>();
+        };
+        let first = searched();
+        assert!(!first.is_empty());
+        assert_eq!(first, searched());
+    }
+
+    #[test]
+    fn widens_with_the_visits() {
+        let state = gamerules::get_seeded_state(4);
+        let params = RaveParameters {
+            widening: 0.5,
+            widening_exponent: 0.5,
+            ..DEFAULT_RAVE
+        };
+        let mut mcts = MCTS::new(&Piranhas::from_state(&state), params.clone());
+        mcts.set_seed(5);
+        mcts.search(400);
+        let root = mcts.node_table.get(&mcts.root).expect("No root");
+        let width = params.width(root.n);
+        assert!(width < root.children.len());
+        let searched = root.children.iter().filter(|edge| edge.added).count();
+        assert!(searched > 1 && searched <= width);
+        assert!(root.children[width..].iter().all(|edge| !edge.added));
+    }
+
+    #[test]
+    fn proves_a_connecting_move() {
+        // red joins its two fishes in one move, blue can neither connect nor capture
+        let mut fields = [[FieldType::Free; 10]; 10];
+        fields[0][0] = FieldType::RedPlayer;
+        fields[2][1] = FieldType::RedPlayer;
+        fields[9][9] = FieldType::BluePlayer;
+        fields[9][5] = FieldType::BluePlayer;
+        let mut state = GameState::new(Board::new(fields), 58);
+        let mut mcts = MCTS::new(&Piranhas::from_state(&state), DEFAULT_RAVE);
+        mcts.set_seed(6);
+        mcts.search(5000);
+        let (action, _, outcome) = mcts.best_action();
+        assert_eq!(outcome, Some(Outcome::Win(2)));
+        state.perform(&action.expect("No move found"), &PlayerColor::Red);
+        assert!(state.is_connected(&PlayerColor::Red));
+    }
+
+    #[test]
+    fn puct_follows_the_priors() {
+        let state = gamerules::get_seeded_state(5);
+        let mut rated = LogicBasedPlayer::get_rated_moves(&state);
+        rated.sort_unstable_by_key(|&(rate, _)| rate);
+        let priors = PriorSource::Variance.priors(&state, &rated, 50.);
+        assert!((priors.iter().sum::<f32>() - 1.).abs() < 1e-4);
+        assert!(priors.windows(2).all(|pair| pair[0] >= pair[1]));
+        let greedy = PriorSource::Variance.priors(&state, &rated, 0.);
+        assert!(greedy[0] > 0. && greedy[greedy.len() - 1] == 0.);
+        assert!(PriorSource::from_string("uniform").is_ok());
+        assert!(PriorSource::from_string("network").is_err());
+
+        let mut params = RaveParameters::empty();
+        let values = [
+            ("c_base", "19652"),
+            ("fpu_r", "0.01"),
+            ("puct", "1.5"),
+            ("prior_temperature", "50"),
+        ];
+        for &(key, val) in values.iter() {
+            params.set_var_from_string(key.to_string(), val.to_string());
+        }
+        let mut mcts = MCTS::new(&Piranhas::from_state(&state), params);
+        mcts.set_seed(7);
+        mcts.search(500);
+        let (action, _, _) = mcts.best_action();
+        assert!(state
+            .get_move_list()
+            .contains(&action.expect("No move found")));
+    }
+}
@@ -0,0 +1,68 @@
+use game_sdk::Move;
+use serde_json;
+
+use super::solver::Outcome;
+
+/// A node of an exported search tree
+#[derive(Clone, Debug, Serialize)]
+pub struct ExportNode {
+    pub id: usize,
+    /// `None` at the root. A transposition is only exported below the first parent found
+    pub parent: Option<usize>,
+    /// the move from the parent, `None` at the root
+    pub action: Option<Move>,
+    pub visits: f32,
+    /// mean result for the player who made `action`
+    pub q: f32,
+    /// RAVE statistics of `action`, gathered all over the tree
+    pub rave_visits: f32,
+    pub rave_q: f32,
+    /// outcome for the side to move once the search proved it
+    pub proof: Option<Outcome>,
+}
+
+/// The most visited part of a search tree, the root first and parents before their children
+#[derive(Clone, Debug, Serialize)]
+pub struct TreeExport {
+    pub turn: u8,
+    pub nodes: Vec<ExportNode>,
+}
+
+impl TreeExport {
+    pub fn to_json(&self) -> String {
+        return serde_json::to_string_pretty(self).expect("Could not serialize tree");
+    }
+
+    /// Graphviz graph of the tree, edges drawn thicker the more visits they got
+    pub fn to_dot(&self) -> String {
+        let root_visits = self
+            .nodes
+            .first()
+            .map_or(1., |root| f32::max(root.visits, 1.));
+        let mut text = format!(
+            "digraph mcts_turn_{} {{\n    node [shape=box];\n",
+            self.turn
+        );
+        for node in self.nodes.iter() {
+            let mut label = format!("n={} q={:.3}", node.visits, node.q);
+            if node.action.is_some() {
+                label += &format!("\\nrave n={} q={:.3}", node.rave_visits, node.rave_q);
+            }
+            if let Some(proof) = node.proof {
+                label += &format!("\\n{:?}", proof);
+            }
+            text += &format!("    n{} [label=\"{}\"];\n", node.id, label);
+            if let (Some(parent), Some(action)) = (node.parent, node.action) {
+                text += &format!(
+                    "    n{} -> n{} [label=\"{}\", penwidth={:.2}];\n",
+                    parent,
+                    node.id,
+                    action,
+                    1. + 4. * node.visits / root_visits
+                );
+            }
+        }
+        text += "}\n";
+        return text;
+    }
+}
@@ -0,0 +1,12 @@
+//! The MCTS engine the RAVE players are presets of, see `RaveParameters`
+
+mod engine;
+mod export;
+mod params;
+mod piranhas;
+pub mod solver;
+
+pub use self::engine::{DEFAULT_NODE_BUDGET, MCTS};
+pub use self::export::{ExportNode, TreeExport};
+pub use self::params::{FinalMove, PriorSource, RaveParameters, DEFAULT_RAVE, LEGACY_RAVE};
+pub use self::piranhas::Piranhas;
@@ -0,0 +1,198 @@
+use game_sdk::{GameState, Move};
+use std::sync::Arc;
+
+use crate::eval::Network;
+
+/// Where the PUCT selection takes the prior probability of a move from
+#[derive(Clone, Debug)]
+pub enum PriorSource {
+    /// every move is as likely
+    Uniform,
+    /// softmax over the variance a move leaves, like `get_rated_moves` rates it
+    Variance,
+    /// the policy head of a network, uniform without one
+    Network(Arc<Network>),
+}
+
+impl PriorSource {
+    /// Reads a source written as `uniform`, `variance` or `network=<weights file>`
+    pub fn from_string(text: &str) -> Result<PriorSource, String> {
+        let mut parts = text.splitn(2, '=');
+        return match (parts.next().unwrap_or(""), parts.next()) {
+            ("uniform", None) => Ok(PriorSource::Uniform),
+            ("variance", None) => Ok(PriorSource::Variance),
+            ("network", Some(path)) => {
+                Ok(PriorSource::Network(Arc::new(Network::from_file(path)?)))
+            }
+            _ => Err(format!("Unknown prior {}", text)),
+        };
+    }
+
+    /// Priors of the `rated` moves in `state`, in the same order. The softmax over the
+    /// variance uses `temperature` in units of variance, at 0 only the best moves get a prior
+    pub fn priors(&self, state: &GameState, rated: &[(i32, Move)], temperature: f32) -> Vec<f32> {
+        let uniform = vec![1. / rated.len() as f32; rated.len()];
+        return match *self {
+            PriorSource::Uniform => uniform,
+            PriorSource::Variance => {
+                let best = rated.iter().map(|&(rate, _)| rate).min().unwrap_or(0);
+                let weights: Vec<f32> = rated
+                    .iter()
+                    .map(|&(rate, _)| {
+                        if temperature > 0. {
+                            (-((rate - best) as f32) / temperature).exp()
+                        } else {
+                            (rate == best) as i32 as f32
+                        }
+                    })
+                    .collect();
+                let sum: f32 = weights.iter().sum();
+                weights.iter().map(|weight| weight / sum).collect()
+            }
+            PriorSource::Network(ref network) => {
+                let moves: Vec<Move> = rated.iter().map(|&(_, action)| action).collect();
+                network.policy(state, &moves)
+            }
+        };
+    }
+}
+
+/// Which root child the search plays once it is over
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum FinalMove {
+    /// the child with the most visits, the search value reported is its number of visits
+    MostVisited,
+    /// the child with the best mean result
+    BestValue,
+}
+
+impl FinalMove {
+    /// Reads `visits` or `value`
+    pub fn from_string(text: &str) -> Result<FinalMove, String> {
+        return match text {
+            "visits" => Ok(FinalMove::MostVisited),
+            "value" => Ok(FinalMove::BestValue),
+            _ => Err(format!("Unknown final move {}", text)),
+        };
+    }
+}
+
+/// Settings of the MCTS. The exploration constant grows with the visits of a node,
+/// `c + c_factor * ln((1 + N + c_base) / c_base)`, unvisited children start at the value of
+/// their parent lowered by `fpu_r` and `b_squared` sets how fast RAVE values fade out
+#[derive(Clone, Debug)]
+pub struct RaveParameters {
+    pub c: f32,
+    pub c_base: usize,
+    pub c_factor: f32,
+    pub fpu_r: f32,
+    pub b_squared: f32,
+    /// weight of the PUCT exploration term `prior * sqrt(N) / (1 + n)`. At 0 the UCT term
+    /// weighted by `c` is used instead and no priors are computed
+    pub puct: f32,
+    pub prior: PriorSource,
+    pub prior_temperature: f32,
+    pub final_move: FinalMove,
+    /// progressive widening: a node with `N` visits searches its best
+    /// `1 + widening * N^widening_exponent` children by heuristic rank, proven ones not
+    /// counted. At 0 every child is searched from the start
+    pub widening: f32,
+    pub widening_exponent: f32,
+}
+
+impl RaveParameters {
+    /// All numbers 0, priors from the variance so CLOP only has to tune `puct` and
+    /// `prior_temperature` to try PUCT
+    pub fn empty() -> RaveParameters {
+        return RaveParameters {
+            c: 0.,
+            c_base: 0,
+            c_factor: 0.,
+            fpu_r: 0.,
+            b_squared: 0.,
+            puct: 0.,
+            prior: PriorSource::Variance,
+            prior_temperature: 0.,
+            final_move: FinalMove::BestValue,
+            widening: 0.,
+            widening_exponent: 0.,
+        };
+    }
+
+    /// Number of children a node with `visits` searches
+    pub fn width(&self, visits: f32) -> usize {
+        if self.widening <= 0. {
+            return usize::MAX;
+        }
+        return 1 + (self.widening * visits.powf(self.widening_exponent)) as usize;
+    }
+
+    pub fn set_var_from_string(&mut self, identifier: String, val: String) {
+        match &identifier[..] {
+            "c" => {
+                self.c = val.parse().expect("Got wrong val");
+            }
+            "c_base" => {
+                self.c_base = val.parse().expect("Got wrong val");
+            }
+            "c_factor" => {
+                self.c_factor = val.parse().expect("Got wrong val");
+            }
+            "fpu_r" => {
+                self.fpu_r = val.parse().expect("Got wrong val");
+            }
+            "b_squared" => {
+                self.b_squared = val.parse().expect("Got wrong val");
+            }
+            "puct" => {
+                self.puct = val.parse().expect("Got wrong val");
+            }
+            "prior" => {
+                self.prior = PriorSource::from_string(&val).expect("Got wrong val");
+            }
+            "prior_temperature" => {
+                self.prior_temperature = val.parse().expect("Got wrong val");
+            }
+            "final_move" => {
+                self.final_move = FinalMove::from_string(&val).expect("Got wrong val");
+            }
+            "widening" => {
+                self.widening = val.parse().expect("Got wrong val");
+            }
+            "widening_exponent" => {
+                self.widening_exponent = val.parse().expect("Got wrong val");
+            }
+            _ => panic!("wrong identifier"),
+        };
+    }
+}
+
+/// What `RavePlayer` searches with
+pub const DEFAULT_RAVE: RaveParameters = RaveParameters {
+    c: 0.038,
+    c_base: 7000,
+    c_factor: 0.589,
+    fpu_r: -0.261,
+    b_squared: 0.788,
+    puct: 0.,
+    prior: PriorSource::Variance,
+    prior_temperature: 0.,
+    final_move: FinalMove::MostVisited,
+    widening: 0.,
+    widening_exponent: 0.,
+};
+
+/// What `LegacyRavePlayer` searches with, it plays the child with the best value
+pub const LEGACY_RAVE: RaveParameters = RaveParameters {
+    c: 0.038,
+    c_base: 7000,
+    c_factor: 0.589,
+    fpu_r: -0.261,
+    b_squared: 0.788,
+    puct: 0.,
+    prior: PriorSource::Variance,
+    prior_temperature: 0.,
+    final_move: FinalMove::BestValue,
+    widening: 0.,
+    widening_exponent: 0.,
+};
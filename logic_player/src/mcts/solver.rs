@@ -0,0 +1,93 @@
+use game_sdk::{gamerules, GameState};
+
+pub use crate::search::solver::Outcome;
+
+/// Outcome of `state` for the side to move if the game is over. Like in the alpha-beta players a
+/// side without moves has lost
+pub fn terminal_outcome(state: &GameState) -> Option<Outcome> {
+    if gamerules::is_finished(state) {
+        return Some(match gamerules::get_winner(state) {
+            Some(color) if color == state.get_current_player_color() => Outcome::Win(0),
+            Some(_) => Outcome::Loss(0),
+            None => Outcome::Draw,
+        });
+    }
+    if state.get_move_list().is_empty() {
+        return Some(Outcome::Loss(0));
+    }
+    return None;
+}
+
+/// Value of a node proven to end in `outcome` for the player who moved into it, the point of
+/// view of the node statistics
+pub fn value_of(outcome: Outcome) -> f32 {
+    return match outcome {
+        Outcome::Win(_) => 0.,
+        Outcome::Draw => 0.5,
+        Outcome::Loss(_) => 1.,
+    };
+}
+
+/// Proves a node from the outcomes of its children, each for the side to move in the child and
+/// `None` while unproven. Returns the position of the child the side to move picks together
+/// with the outcome of the node, `None` if the node is still open.
+///
+/// A single lost child proves a win, the quickest one is taken. Otherwise all children have to
+/// be proven: the node is drawn if one of them is, lost as late as possible if not.
+pub fn prove<I>(children: I) -> Option<(usize, Outcome)>
+where
+    I: IntoIterator<Item = Option<Outcome>>,
+{
+    let mut win: Option<(usize, u8)> = None;
+    let mut draw = None;
+    let mut loss: Option<(usize, u8)> = None;
+    let mut open = false;
+    for (index, outcome) in children.into_iter().enumerate() {
+        match outcome {
+            Some(Outcome::Loss(distance)) => {
+                if win.map_or(true, |(_, quickest)| distance < quickest) {
+                    win = Some((index, distance));
+                }
+            }
+            Some(Outcome::Draw) => {
+                if draw.is_none() {
+                    draw = Some(index);
+                }
+            }
+            Some(Outcome::Win(distance)) => {
+                if loss.map_or(true, |(_, longest)| distance > longest) {
+                    loss = Some((index, distance));
+                }
+            }
+            None => open = true,
+        }
+    }
+    if let Some((index, distance)) = win {
+        return Some((index, Outcome::Win(distance.saturating_add(1))));
+    }
+    if open {
+        return None;
+    }
+    if let Some(index) = draw {
+        return Some((index, Outcome::Draw));
+    }
+    return loss.map(|(index, distance)| (index, Outcome::Loss(distance.saturating_add(1))));
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn proves_from_children() {
+        let open = [None, Some(Outcome::Win(3)), Some(Outcome::Draw)];
+        assert_eq!(prove(open.iter().cloned()), None);
+        let winning = [None, Some(Outcome::Loss(4)), Some(Outcome::Loss(2))];
+        assert_eq!(prove(winning.iter().cloned()), Some((2, Outcome::Win(3))));
+        let drawn = [Some(Outcome::Win(1)), Some(Outcome::Draw)];
+        assert_eq!(prove(drawn.iter().cloned()), Some((1, Outcome::Draw)));
+        let lost = [Some(Outcome::Win(1)), Some(Outcome::Win(5))];
+        assert_eq!(prove(lost.iter().cloned()), Some((1, Outcome::Loss(6))));
+        assert_eq!(prove(Vec::new()), None);
+    }
+}
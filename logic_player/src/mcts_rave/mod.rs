@@ -1,5 +1,3 @@
-mod mcts;
-mod piranhas;
 mod player;
 
 pub use self::player::RavePlayer;
\ No newline at end of file
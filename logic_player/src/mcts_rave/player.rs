@@ -1,41 +1,183 @@
-use super::mcts::MCTS;
-use super::piranhas::Piranhas;
 use game_sdk::gamerules;
-use game_sdk::logging::{Data, MoveValuePair, State};
+use game_sdk::logging::{send_report, Data, MoveValuePair, State};
 use game_sdk::ClientListener;
 use game_sdk::{GameState, Move};
-use std::sync::mpsc;
-
+use rand::rngs::StdRng;
+use std::fs;
+use std::sync::{mpsc, Arc};
 use time;
 
+use crate::book::OpeningBook;
+use crate::eval::Network;
+use crate::limit::{engine_rng, SearchClock, SearchLimit, RAVE_LIMIT};
+use crate::mcts::{Piranhas, TreeExport, DEFAULT_NODE_BUDGET, DEFAULT_RAVE, MCTS};
+use crate::playout::{GreedyPlayout, PlayoutPolicy};
+
+fn write_export(tree: &TreeExport, directory: &str) -> Result<(), String> {
+    fs::create_dir_all(directory).map_err(|e| format!("{}: {}", directory, e))?;
+    let path = format!("{}/turn_{}", directory, tree.turn);
+    fs::write(format!("{}.dot", path), tree.to_dot()).map_err(|e| e.to_string())?;
+    fs::write(format!("{}.json", path), tree.to_json()).map_err(|e| e.to_string())?;
+    return Ok(());
+}
+
 #[derive(Clone)]
 pub struct RavePlayer {
     tx: Option<mpsc::Sender<Data>>,
     id: i64,
     mcts: Option<MCTS>,
+    network: Option<Arc<Network>>,
+    book: Option<Arc<OpeningBook>>,
+    book_randomness: f32,
+    confidence: f32,
+    playout: Arc<dyn PlayoutPolicy>,
+    max_nodes: usize,
+    searches: u32,
+    iterations_per_s: f32,
+    /// directory and size of the trees exported after every search
+    export: Option<(String, usize)>,
+    limit: SearchLimit,
+    seed: Option<u64>,
+    rng: StdRng,
 }
 
 impl RavePlayer {
     pub fn new(tx: Option<mpsc::Sender<Data>>, id: i64) -> RavePlayer {
-        return RavePlayer { tx, id, mcts: None };
+        return RavePlayer {
+            tx,
+            id,
+            mcts: None,
+            network: None,
+            book: None,
+            book_randomness: 0.,
+            confidence: 0.,
+            playout: Arc::new(GreedyPlayout),
+            max_nodes: DEFAULT_NODE_BUDGET,
+            searches: 0,
+            iterations_per_s: 0.,
+            export: None,
+            limit: RAVE_LIMIT,
+            seed: None,
+            rng: engine_rng(None),
+        };
+    }
+
+    /// Ends every search at `limit`. With `SearchLimit::Nodes` the player also stops
+    /// pondering, so a seeded player repeats its moves
+    pub fn set_limit(&mut self, limit: SearchLimit) {
+        self.limit = limit;
+    }
+
+    /// Draws the random playout and book moves from generators seeded with `seed`
+    pub fn set_seed(&mut self, seed: u64) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_seed(seed);
+        }
+        self.seed = Some(seed);
+        self.rng = engine_rng(Some(seed));
+    }
+
+    /// Lets the search expand moves in the order of the policy of `network`
+    pub fn set_network(&mut self, network: Arc<Network>) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_network(Some(network.clone()));
+        }
+        self.network = Some(network);
+    }
+
+    /// Plays the games of new nodes out with `policy` instead of the greedy playout
+    pub fn set_playout(&mut self, policy: Arc<dyn PlayoutPolicy>) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_playout(policy.clone());
+        }
+        self.playout = policy;
+    }
+
+    /// Limits the search tree to `max_nodes` nodes
+    pub fn set_node_budget(&mut self, max_nodes: usize) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_node_budget(max_nodes);
+        }
+        self.max_nodes = max_nodes;
+    }
+
+    fn new_mcts(&self, game: &Piranhas) -> MCTS {
+        let mut mcts = MCTS::new(game, DEFAULT_RAVE);
+        mcts.set_network(self.network.clone());
+        mcts.set_playout(self.playout.clone());
+        mcts.set_node_budget(self.max_nodes);
+        if let Some(seed) = self.seed {
+            mcts.set_seed(seed);
+        }
+        return mcts;
+    }
+
+    /// Mean speed of all searches of this player so far
+    pub fn iterations_per_s(&self) -> f32 {
+        return self.iterations_per_s;
+    }
+
+    /// Share of the root visits the last move found by the search got
+    pub fn confidence(&self) -> f32 {
+        return self.confidence;
+    }
+
+    /// The `max_nodes` most visited nodes of the current search tree
+    pub fn export_tree(&self, max_nodes: usize) -> Option<TreeExport> {
+        return self.mcts.as_ref().map(|mcts| mcts.export(max_nodes));
+    }
+
+    /// Writes the `max_nodes` most visited nodes of the tree to `directory` after every
+    /// search, as `turn_<turn>.dot` and `turn_<turn>.json`
+    pub fn set_tree_export(&mut self, directory: String, max_nodes: usize) {
+        self.export = Some((directory, max_nodes));
+    }
+
+    /// Plays moves from `book` while the position is in it, see `OpeningBook::lookup`
+    pub fn set_book(&mut self, book: Arc<OpeningBook>, randomness: f32) {
+        self.book = Some(book);
+        self.book_randomness = randomness;
     }
 
     pub fn move_with_id(&mut self, state: &GameState, id: i64) -> Move {
-        let before = time::now();
+        return self.move_since(state, id, time::now());
+    }
+
+    /// Like `move_with_id` for a move request that arrived at `start`, the time spent on it
+    /// before counts against the limit
+    pub fn move_since(&mut self, state: &GameState, id: i64, start: time::Tm) -> Move {
+        if let Some(ref book) = self.book {
+            if let Some(action) = book.lookup(state, self.book_randomness, &mut self.rng) {
+                self.confidence = 1.;
+                return action;
+            }
+        }
+        let clock = SearchClock::started_at(start, self.limit);
         let game = Piranhas::from_state(state);
         if let Some(ref mut mcts) = self.mcts {
             mcts.set_root(&game);
         // *mcts = MCTS::new(&game); // to deactivate taking knowledge over from last turn
         } else {
-            self.mcts = Some(MCTS::new(&game));
+            self.mcts = Some(self.new_mcts(&game));
         }
         if let Some(ref mut mcts) = self.mcts {
             let before_samples = mcts.get_root_samples();
-            let c = 0.038;
-            // mcts.search(1000, c);
-            let budget_seconds = 0.1 - ((time::now() - before).num_milliseconds() as f32 / 1000.);
-            mcts.search_time(budget_seconds, c);
-            if let (Some(action), value, depth) = mcts.best_action() {
+            // mcts.search(1000);
+            mcts.search_until(&clock);
+            self.searches += 1;
+            self.iterations_per_s +=
+                (mcts.iterations_per_s - self.iterations_per_s) / self.searches as f32;
+            if let Some((ref directory, max_nodes)) = self.export {
+                if let Err(e) = write_export(&mcts.export(max_nodes), directory) {
+                    println!("Could not export the tree: {}", e);
+                }
+            }
+            if let (Some(action), _, _) = mcts.best_action() {
+                self.confidence = mcts
+                    .get_pairs()
+                    .iter()
+                    .find(|pair| pair.1 == action)
+                    .map_or(0., |pair| pair.0);
                 if let Some(ref tx) = self.tx {
                     let moves = mcts    
                         .get_pairs()
@@ -52,24 +194,13 @@ impl RavePlayer {
                         data: Vec::new(),
                     };
                     tx.send(Data::Step(send_state)).unwrap();
-                } else if id < 0 {
-                    let stats = mcts.tree_statistics();
-                    if let Some(depth) = depth {
-                        print!("end in {}; ", depth);
-                    }
-                    println!(
-                        "|{}| reused {} samples; {}ms | {} nodes | {}-{} depth | val {:.3} | {} table|{:.0}it/s)",
-                        state.turn,
-                        before_samples,
-                        (time::now() - before).num_milliseconds(),
-                        stats.nodes,
-                        stats.min_depth,
-                        stats.max_depth,
-                        value,
-                        mcts.table_size(),
-                        mcts.iterations_per_s,
-                    );
-                    // println!("{}", action);
+                }
+                if self.tx.is_some() || id < 0 {
+                    let mut report = mcts.report("rave", id, clock.elapsed_ms());
+                    report
+                        .stats
+                        .push(("reused_samples".to_string(), before_samples));
+                    send_report(&self.tx, report);
                 }
                 return action;
             } else {
@@ -92,4 +223,29 @@ impl ClientListener for RavePlayer {
         let id = self.id;
         return self.move_with_id(state, id);
     }
+
+    /// Moves the tree along with the game, so pondering searches the position on the board
+    fn on_update_state(&mut self, state: &GameState) {
+        let game = Piranhas::from_state(state);
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.set_root(&game);
+        } else {
+            self.mcts = Some(self.new_mcts(&game));
+        }
+    }
+
+    /// Ponders once the tree exists, the tree grown here is reused by the next move request
+    fn ponders(&self) -> bool {
+        // the extra iterations would make node-limited searches depend on the opponent's time
+        if let SearchLimit::Nodes(_) = self.limit {
+            return false;
+        }
+        return self.mcts.is_some();
+    }
+
+    fn on_idle(&mut self) {
+        if let Some(ref mut mcts) = self.mcts {
+            mcts.search_time(0.001);
+        }
+    }
 }
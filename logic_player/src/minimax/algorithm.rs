@@ -1,19 +1,28 @@
 use game_sdk::{gamerules, GameState, Move, PlayerColor};
 
-use super::evaluation::clop_state as eval_state;
-use super::transposition::{EntryType, MinimalState, TranspositionTable};
+use super::player::MinimaxParameters;
+use crate::eval::{Evaluator, Term};
+use crate::limit::SearchClock;
+use crate::search::ordering::MoveOrdering;
+use crate::search::pruning::{is_capture, null_move, PruningStatistics};
+use crate::search::quiescence::Quiescence;
+use crate::search::transposition::{EntryType, MinimalState, TranspositionTable};
 
 use std::f32;
 
 pub static MATE_SCORE: f32 = 200_000.0;
 pub static MAX_MATE_PENALTY: f32 = 32.;
-static FUTILITY_MARGIN: f32 = 38.641;
 
 pub struct SearchStatistics {
     pub nodes: usize,
+    /// nodes visited by the quiescence search, not included in `nodes`
+    pub q_nodes: usize,
+    pub pruning: PruningStatistics,
+    /// null window searches that failed high and had to be repeated with the full window
     pub re_searched: usize,
     pub probed: usize,
     pub aspire_probed: usize,
+    /// aspiration window searches at the root that failed low or high and were widened
     pub aspire_re: usize,
 }
 
@@ -21,6 +30,8 @@ impl SearchStatistics {
     pub fn new() -> SearchStatistics {
         return SearchStatistics {
             nodes: 0,
+            q_nodes: 0,
+            pruning: PruningStatistics::default(),
             re_searched: 0,
             probed: 0,
             aspire_probed: 0,
@@ -29,6 +40,26 @@ impl SearchStatistics {
     }
 }
 
+/// `rate_mate` split into the swarm bonus and the result of the game
+pub fn explain_mate(state: &GameState) -> Vec<Term> {
+    let swarm_bonus = state.greatest_swarm_size(&PlayerColor::Red) as f32
+        - state.greatest_swarm_size(&PlayerColor::Blue) as f32;
+    return vec![
+        Term {
+            name: "SWARM_BONUS",
+            color: None,
+            raw: swarm_bonus,
+            contribution: swarm_bonus,
+        },
+        Term {
+            name: "RESULT",
+            color: None,
+            raw: rate_mate(state) - swarm_bonus,
+            contribution: rate_mate(state) - swarm_bonus,
+        },
+    ];
+}
+
 pub fn rate_mate(state: &GameState) -> f32 {
     let red_size = state.greatest_swarm_size(&PlayerColor::Red);
     let blue_size = state.greatest_swarm_size(&PlayerColor::Blue);
@@ -79,7 +110,10 @@ pub fn minimax_rate(
     beta: f32,
     depth: u8,
     tt: &mut TranspositionTable,
-    start_time: &time::Tm,
+    ordering: &mut MoveOrdering,
+    clock: &SearchClock,
+    params: &MinimaxParameters,
+    evaluator: &dyn Evaluator,
 ) -> f32 {
     let mut state = state.clone();
     let color = state.get_current_player_color();
@@ -92,41 +126,14 @@ pub fn minimax_rate(
         beta,
         depth,
         tt,
-        start_time,
+        ordering,
+        Some(*action),
+        clock,
+        params,
+        evaluator,
     );
 }
 
-#[allow(dead_code)]
-pub fn q_search(state: &GameState, mut alpha: f32, beta: f32, player_index: i8) -> f32 {
-    let color = state.get_current_player_color();
-    let standing_pat = player_index as f32 * eval_state(state);
-    if standing_pat + FUTILITY_MARGIN < alpha {
-        return standing_pat;
-    }
-    alpha = f32::max(alpha, standing_pat);
-    let mut best = standing_pat;
-    if alpha > beta {
-        return alpha;
-    }
-    for action in state.get_captures() {
-        let mut state = state.clone();
-        state.perform(&action, &color);
-        let rate;
-        if gamerules::is_finished(&state) {
-            rate = 0.9 * rate_mate(&state) * player_index as f32;
-        } else {
-            rate = -q_search(&state, -beta, -alpha, -player_index);
-        }
-        alpha = f32::max(alpha, rate);
-        best = f32::max(best, rate);
-        if alpha > beta {
-            return alpha;
-        }
-    }
-    // return alpha;
-    return best;
-}
-
 pub fn minimax_rate_state(
     stats: &mut SearchStatistics,
     state: &GameState,
@@ -135,13 +142,16 @@ pub fn minimax_rate_state(
     mut beta: f32,
     depth: u8,
     tt: &mut TranspositionTable,
-    start_time: &time::Tm,
+    ordering: &mut MoveOrdering,
+    previous: Option<Move>,
+    clock: &SearchClock,
+    params: &MinimaxParameters,
+    evaluator: &dyn Evaluator,
 ) -> f32 {
     stats.nodes += 1;
     let hash = MinimalState::from_state(&state);
     let start_alpha = alpha;
     let mut moves: Vec<Move>;
-    let move_len;
     let mut found_move = None;
     if let Some(data) = tt.lookup(&hash) {
         // data = (value, depth, move)
@@ -152,8 +162,6 @@ pub fn minimax_rate_state(
                 EntryType::LowerBound => alpha = f32::max(alpha, data.value),
             }
             if alpha > beta {
-                stats.aspire_probed += 1;
-                stats.aspire_re += 1;
                 return alpha;
             }
         }
@@ -163,11 +171,18 @@ pub fn minimax_rate_state(
             return rate_mate(state) * player_index as f32;
         }
     }
-    /*if depth == 0 {
-        return player_index as f32 * eval_state(state);
-    }*/
     if depth == 0 {
-        return q_search(state, alpha, beta, player_index);
+        if !params.search_q {
+            return player_index as f32 * evaluator.evaluate(state);
+        }
+        let mut quiescence = Quiescence::new(
+            &params.quiescence,
+            |state: &GameState| evaluator.evaluate(state),
+            |state: &GameState| 0.9 * rate_mate(state),
+        );
+        let rate = quiescence.search(state, alpha, beta, player_index);
+        stats.q_nodes += quiescence.nodes;
+        return rate;
     }
 
     if let Some(action) = found_move {
@@ -180,15 +195,17 @@ pub fn minimax_rate_state(
             -alpha,
             depth - 1,
             tt,
-            start_time,
+            ordering,
+            clock,
+            params,
+            evaluator,
         );
         if rate.is_nan() {
             return f32::NAN;
         }
         if rate > alpha {
             if rate > beta {
-                stats.aspire_probed += 1;
-                stats.aspire_re += 1;
+                ordering.cutoff(&action, state, previous, depth, 0);
                 tt.insert(&hash, rate, depth, &action, EntryType::LowerBound);
                 return rate;
             }
@@ -196,28 +213,75 @@ pub fn minimax_rate_state(
         }
     }
 
-    if depth > 2 && (time::now() - *start_time).num_milliseconds() > 1700 {
+    if depth > 2 && clock.expired(stats.nodes) {
         return f32::NAN;
     }
 
     moves = state.get_move_list();
-    move_len = moves.len();
-    if move_len <= 0 {
+    if moves.is_empty() {
         return -MATE_SCORE * player_index as f32;
     }
+    ordering.order(&mut moves, state, previous);
+    if let Some(action) = found_move {
+        if let Some(index) = moves.iter().position(|&m| m == action) {
+            moves.remove(index);
+            moves.insert(0, action);
+        }
+    }
+
+    let null_window = beta - alpha < 1e-3;
+    let pruning = &params.pruning;
+    // only the null move and futility pruning look at the static evaluation
+    let static_eval = if pruning.null_move || pruning.futility {
+        player_index as f32 * evaluator.evaluate(state)
+    } else {
+        0.
+    };
+    if static_eval > beta && pruning.allows_null_move(state, previous, depth, null_window) {
+        stats.pruning.null_tried += 1;
+        let rate = -minimax_rate_state(
+            stats,
+            &null_move(state),
+            -player_index,
+            -beta - 1e-5,
+            -beta,
+            depth - 1 - pruning.null_move_reduction,
+            tt,
+            ordering,
+            None,
+            clock,
+            params,
+            evaluator,
+        );
+        if rate.is_nan() {
+            return f32::NAN;
+        }
+        if rate > beta {
+            stats.pruning.null_cutoffs += 1;
+            // a mate found after passing is no proof of anything
+            return f32::min(rate, MATE_SCORE - 1.);
+        }
+    }
 
-    let mut drain = moves.drain(0..move_len);
-    let mut best_move = None; // drain.next().expect("Did not find first move");
+    let mut best_move = None;
     let mut best = f32::NEG_INFINITY;
+    let mut cutoff_index = None;
     let color = state.get_current_player_color();
-    while let Some(action_considered) = drain.next() {
+    for (index, action_considered) in moves.into_iter().enumerate() {
+        let capture = is_capture(state, &action_considered);
+        if best_move.is_some()
+            && null_window
+            && pruning.is_futile(static_eval, alpha, depth, capture)
+        {
+            stats.pruning.futile += 1;
+            continue;
+        }
         let mut state = state.clone();
         state.perform(&action_considered, &color);
-        let mut rate;
-        if best_move != None && (beta - alpha < 1e-3) && depth >= 2 {
-            let static_eval = eval_state(&state) * player_index as f32;
-            if static_eval < alpha {
-                rate = -minimax_rate_state(
+        if best_move.is_some() && null_window && depth >= 2 && pruning.probes {
+            let child_eval = player_index as f32 * evaluator.evaluate(&state);
+            if child_eval < alpha || child_eval - pruning.probe_margin > beta {
+                let rate = -minimax_rate_state(
                     stats,
                     &state,
                     -player_index,
@@ -225,41 +289,30 @@ pub fn minimax_rate_state(
                     -alpha,
                     depth - 2,
                     tt,
-                    start_time,
+                    ordering,
+                    Some(action_considered),
+                    clock,
+                    params,
+                    evaluator,
                 );
-                if rate < best {
+                if rate.is_nan() {
+                    return f32::NAN;
+                }
+                if child_eval < alpha && rate < best {
+                    stats.pruning.probe_cuts += 1;
                     continue;
                 }
-            } else if static_eval - FUTILITY_MARGIN > beta {
-                rate = -minimax_rate_state(
-                    stats,
-                    &state,
-                    -player_index,
-                    -alpha - 1e-5,
-                    -alpha,
-                    depth - 2,
-                    tt,
-                    start_time,
-                );
-                if rate - FUTILITY_MARGIN > beta {
+                if child_eval >= alpha && rate - pruning.probe_margin > beta {
+                    stats.pruning.probe_cuts += 1;
                     return rate;
                 }
             }
         }
-        rate = -minimax_rate_state(
-            stats,
-            &state,
-            -player_index,
-            -alpha - 1e-5,
-            -alpha,
-            depth - 1,
-            tt,
-            start_time,
-        );
-        if rate.is_nan() {
-            return f32::NAN;
-        }
-        if alpha < rate && rate <= beta {
+        let mut rate;
+        // with PVS only the first move gets the full window, all later ones just have to
+        // prove they are not better and are searched again if that fails
+        let full_window = !params.pvs || (best_move.is_none() && found_move.is_none());
+        if full_window {
             rate = -minimax_rate_state(
                 stats,
                 &state,
@@ -268,22 +321,87 @@ pub fn minimax_rate_state(
                 -alpha,
                 depth - 1,
                 tt,
-                start_time,
+                ordering,
+                Some(action_considered),
+                clock,
+                params,
+                evaluator,
+            );
+        } else {
+            let reduced_depth = pruning.reduced_depth(depth, index, capture);
+            rate = -minimax_rate_state(
+                stats,
+                &state,
+                -player_index,
+                -alpha - 1e-5,
+                -alpha,
+                reduced_depth,
+                tt,
+                ordering,
+                Some(action_considered),
+                clock,
+                params,
+                evaluator,
             );
+            if reduced_depth < depth - 1 {
+                stats.pruning.reduced += 1;
+                if rate > alpha {
+                    stats.pruning.reduced_re_searched += 1;
+                    rate = -minimax_rate_state(
+                        stats,
+                        &state,
+                        -player_index,
+                        -alpha - 1e-5,
+                        -alpha,
+                        depth - 1,
+                        tt,
+                        ordering,
+                        Some(action_considered),
+                        clock,
+                        params,
+                        evaluator,
+                    );
+                }
+            }
             if rate.is_nan() {
                 return f32::NAN;
             }
+            stats.probed += 1;
+            if alpha < rate && rate <= beta {
+                stats.re_searched += 1;
+                rate = -minimax_rate_state(
+                    stats,
+                    &state,
+                    -player_index,
+                    -beta,
+                    -alpha,
+                    depth - 1,
+                    tt,
+                    ordering,
+                    Some(action_considered),
+                    clock,
+                    params,
+                    evaluator,
+                );
+            }
+        }
+        if rate.is_nan() {
+            return f32::NAN;
         }
         if rate > best {
             alpha = f32::max(rate, alpha);
             best = rate;
             best_move = Some(action_considered);
             if alpha > beta {
-                stats.aspire_probed += 1;
+                cutoff_index = Some(index);
                 break;
             }
         }
     }
+    if let Some(index) = cutoff_index {
+        let action = best_move.expect("Did not find any move after checking");
+        ordering.cutoff(&action, state, previous, depth, index);
+    }
     if best <= start_alpha {
         tt.insert(
             &hash,
@@ -1,9 +1,29 @@
 use game_sdk::GameState;
 use game_sdk::PlayerColor;
 
+use crate::eval::{tapered_terms, texel_feature, Evaluator, Term, DEFAULT_TEXEL};
 use std::f32;
 use util::Helper;
 
+/// The evaluation `MinimaxPlayer` searches with unless told otherwise
+#[derive(Clone)]
+pub struct ClopEvaluator;
+
+impl Evaluator for ClopEvaluator {
+    fn evaluate(&self, state: &GameState) -> f32 {
+        return clop_state(state);
+    }
+
+    fn explain(&self, state: &GameState) -> Vec<Term> {
+        let phase = state.turn as f32 / 60.;
+        let mut terms = Vec::new();
+        for color in [PlayerColor::Red, PlayerColor::Blue].iter() {
+            terms.extend(tapered_terms(&clop_features(state, color), color, phase));
+        }
+        return terms;
+    }
+}
+
 #[allow(dead_code)]
 pub fn rate_swarm(state: &GameState) -> f32 {
     let r_frac = swarm_frac(state, &PlayerColor::Red);
@@ -75,48 +95,8 @@ pub fn variance_rate(state: &GameState) -> f32 {
     return variance_b - variance_r;
 }
 
-fn texel_feature(phase: f32, x: f32, start: f32, end: f32) -> f32 {
-    return phase * x * end + (1. - phase) * x * start;
-}
-
 pub fn texel_state_single(state: &GameState, color: &PlayerColor) -> f32 {
-    let phase = state.turn as f32 / 60.;
-    let mut result = 0.0;
-    let swarm = Helper::greatest_swarm_new(state, color);
-    let mut win: f32 = 0.;
-    let mut len = 0;
-    for (x, y) in state.get_own_fields(color) {
-        win += 1. - (Helper::get_distance_to_swarm_new(x, y, &swarm) as f32 / 9.);
-        len += 1;
-    }
-    //  win, "DISTANCES"
-    win = win / len as f32;
-    // win, "ADJ_DISTANCES"
-    result += texel_feature(phase, win, 1.816, 7.583);
-    // swarm.count_ones() as f32, "SWARM_COUNT"
-    result += texel_feature(phase, swarm.count_ones() as f32 / 16., -3.389, 6.421);
-    // swarm.count_ones() as f32 / 16., "ADJ_SWARM_COUNT",
-
-    let fishes = state.board.get_fields_of(color);
-
-    let mut center = fishes;
-    center.mask(297799908644072875622400);
-    let center_count = center.count_ones() as f32;
-    // center_count, "CENTER_COUNT"
-    // center_count / len as f32, "ADJ_CENTER_COUNT",
-    result += texel_feature(phase, center_count / len as f32, -0.033, 3.777);
-
-    let mut border = fishes;
-    border.mask(1267033445369934637136782821375);
-    let border_count = border.count_ones() as f32;
-    // border_count, "BORDER_COUNT"
-    // border_count / len as f32, "ADJ_BORDER_COUNT",
-    result += texel_feature(phase, border_count / len as f32, -8.652, -0.648);
-
-    // len as f32, "COUNT"
-    result += texel_feature(phase, len as f32 / 16., 5.228, 4.445);
-    // len as f32 / 16., "ADJ_COUNT"
-    return result;
+    return DEFAULT_TEXEL.rate_color(state, color);
 }
 
 #[allow(dead_code)]
@@ -228,11 +208,17 @@ pub fn clop_state(state: &GameState) -> f32 {
         - clop_state_single(state, &PlayerColor::Blue);
 }
 
-#[allow(dead_code)]
 fn clop_state_single(state: &GameState, color: &PlayerColor) -> f32 {
-    let mut result = 0.0;
     let phase = state.turn as f32 / 60.;
+    let mut result = 0.0;
+    for &(_, x, start, end) in clop_features(state, color).iter() {
+        result += texel_feature(phase, x, start, end);
+    }
+    return result;
+}
 
+/// Features of `clop_state` for `color` with their start and end weights
+fn clop_features(state: &GameState, color: &PlayerColor) -> [(&'static str, f32, f32, f32); 4] {
     let swarm = Helper::greatest_swarm_new(state, color);
     let mut dist = 0.;
     let mut len = 0;
@@ -255,15 +241,15 @@ fn clop_state_single(state: &GameState, color: &PlayerColor) -> f32 {
         sum_y += y;
     }
     dist = dist / len as f32;
-    result += texel_feature(phase, dist, 0.686, 7.378);
-    result += texel_feature(phase, swarm.count_ones() as f32, 4.023, 4.754);
-
-    result += texel_feature(phase, len as f32, 6.599, 7.095);
     let var_x = squared_sum_x - sum_x * sum_x / len;
     let var_y = squared_sum_y - sum_y * sum_y / len;
-    result += texel_feature(phase, -((var_x + var_y) as f32), 1.572, -0.196);
 
-    return result;
+    return [
+        ("SWARM_DISTANCE", dist, 0.686, 7.378),
+        ("SWARM_COUNT", swarm.count_ones() as f32, 4.023, 4.754),
+        ("COUNT", len as f32, 6.599, 7.095),
+        ("VARIANCE", -((var_x + var_y) as f32), 1.572, -0.196),
+    ];
 }
 
 #[allow(dead_code)]
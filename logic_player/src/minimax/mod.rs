@@ -1,6 +1,7 @@
 mod player;
-mod transposition;
 pub mod evaluation;
 mod algorithm;
 
-pub use self::player::MinimaxPlayer;
\ No newline at end of file
+pub use self::algorithm::explain_mate;
+pub use self::evaluation::ClopEvaluator;
+pub use self::player::{MinimaxParameters, MinimaxPlayer};
\ No newline at end of file
@@ -1,37 +1,274 @@
 use game_sdk::{ClientListener, GameState, Move, PlayerColor};
 
 use super::algorithm::{minimax_rate_state, SearchStatistics, MATE_SCORE, MAX_MATE_PENALTY};
-use super::transposition::{EntryType, MinimalState, TranspositionTable};
-use crate::util::Helper;
+use super::evaluation::ClopEvaluator;
+use crate::book::OpeningBook;
+use crate::eval::{Evaluator, FeatureVector};
+use crate::limit::{engine_rng, SearchClock, SearchLimit, DEFAULT_LIMIT};
+use crate::search::ordering::MoveOrdering;
+use crate::search::pruning::{PruningParameters, DEFAULT_PRUNING};
+use crate::search::quiescence::{QuiescenceParameters, DEFAULT_QUIESCENCE};
+use crate::search::report::{line_after, rated_root_moves, ratio};
+use crate::search::transposition::{EntryType, MinimalState, TranspositionTable, DEFAULT_SIZE_MB};
 
-use game_sdk::logging::{Data, MoveValuePair, State};
+use game_sdk::logging::{send_report, Data, MoveValuePair, SearchReport, State};
 use std::f32;
-use std::sync::mpsc;
+use rand::rngs::StdRng;
+use std::sync::{mpsc, Arc};
 use time;
 
 static START_DEPTH: u8 = 0;
-// static WINDOW_SIZE: f32 = 39.474;
+
+/// Time a move may take unless `MinimaxPlayer::set_limit` says otherwise
+
+pub const DEFAULT_PARAMETERS: MinimaxParameters = MinimaxParameters {
+	aspiration_window: 39.474,
+	aspiration_growth: 4.,
+	pvs: true,
+	search_q: true,
+	quiescence: DEFAULT_QUIESCENCE,
+	pruning: DEFAULT_PRUNING,
+};
+
+#[derive(Clone)]
+pub struct MinimaxParameters {
+	/// half width of the window around the last iteration's value, 0 searches without one
+	pub aspiration_window: f32,
+	/// factor the window is widened by after the root value fell outside of it
+	pub aspiration_growth: f32,
+	/// search all but the first move with a null window
+	pub pvs: bool,
+	/// resolve pending captures at the horizon instead of evaluating right away
+	pub search_q: bool,
+	pub quiescence: QuiescenceParameters,
+	pub pruning: PruningParameters,
+}
+
+impl MinimaxParameters {
+	pub fn empty() -> MinimaxParameters {
+		MinimaxParameters {
+			aspiration_window: 0.0,
+			aspiration_growth: 0.0,
+			pvs: true,
+			search_q: true,
+			quiescence: QuiescenceParameters {
+				delta_margin: 0.0,
+				max_depth: 0,
+			},
+			pruning: PruningParameters::empty(),
+		}
+	}
+
+	pub fn set_var_from_string(&mut self, identifier: String, val: String) {
+		match &identifier[..] {
+			"aspiration_window" => {
+				self.aspiration_window = val.parse().expect("Got wrong val");
+			}
+			"aspiration_growth" => {
+				self.aspiration_growth = val.parse().expect("Got wrong val");
+			}
+			"pvs" => {
+				self.pvs = val.parse().expect("Got wrong val");
+			}
+			"search_q" => {
+				self.search_q = val.parse().expect("Got wrong val");
+			}
+			"q_delta_margin" => {
+				self.quiescence.delta_margin = val.parse().expect("Got wrong val");
+			}
+			"q_max_depth" => {
+				self.quiescence.max_depth = val.parse().expect("Got wrong val");
+			}
+			_ => {
+				if !self.pruning.set_var_from_string(&identifier, &val) {
+					panic!("Wrong identifier");
+				}
+			}
+		};
+	}
+}
 
 #[derive(Clone)]
 pub struct MinimaxPlayer {
 	id: i64,
 	tt: TranspositionTable,
+	ordering: MoveOrdering,
 	tx: Option<mpsc::Sender<Data>>,
+	params: MinimaxParameters,
+	evaluator: Arc<dyn Evaluator>,
+	book: Option<Arc<OpeningBook>>,
+	book_randomness: f32,
+	limit: SearchLimit,
+	rng: StdRng,
 }
 impl MinimaxPlayer {
 	pub fn new(tx: Option<mpsc::Sender<Data>>, id: i64) -> MinimaxPlayer {
+		return MinimaxPlayer::with_parameters(tx, id, DEFAULT_PARAMETERS);
+	}
+
+	pub fn with_parameters(
+		tx: Option<mpsc::Sender<Data>>,
+		id: i64,
+		params: MinimaxParameters,
+	) -> MinimaxPlayer {
 		return MinimaxPlayer {
 			id,
-			tt: TranspositionTable::new(),
+			tt: TranspositionTable::new(DEFAULT_SIZE_MB),
+			ordering: MoveOrdering::new(),
 			tx,
+			params,
+			evaluator: Arc::new(ClopEvaluator),
+			book: None,
+			book_randomness: 0.,
+			limit: DEFAULT_LIMIT,
+			rng: engine_rng(None),
 		};
 	}
+
+	/// Ends every search at `limit`. With `SearchLimit::Nodes` the player searches the same
+	/// tree on every run, so a seeded player repeats its moves
+	pub fn set_limit(&mut self, limit: SearchLimit) {
+		self.limit = limit;
+	}
+
+	/// Draws the random book moves from a generator seeded with `seed`
+	pub fn set_seed(&mut self, seed: u64) {
+		self.rng = engine_rng(Some(seed));
+	}
+
+	/// Replaces the evaluation the player searches with, `ClopEvaluator` by default
+	pub fn set_evaluator(&mut self, evaluator: Arc<dyn Evaluator>) {
+		self.evaluator = evaluator;
+	}
+
+	/// Plays moves from `book` while the position is in it, see `OpeningBook::lookup`
+	pub fn set_book(&mut self, book: Arc<OpeningBook>, randomness: f32) {
+		self.book = Some(book);
+		self.book_randomness = randomness;
+	}
+}
+
+/// Searches the first root move in a window around the value of the last iteration. Every
+/// time the result falls outside, the window is widened by `aspiration_growth` and searched again
+fn aspiration_search(
+	stats: &mut SearchStatistics,
+	state: &GameState,
+	player_index: i8,
+	previous: f32,
+	depth: u8,
+	tt: &mut TranspositionTable,
+	ordering: &mut MoveOrdering,
+	root_move: &Move,
+	clock: &SearchClock,
+	params: &MinimaxParameters,
+	evaluator: &dyn Evaluator,
+) -> f32 {
+	let full = MATE_SCORE + MAX_MATE_PENALTY;
+	let mut window = params.aspiration_window;
+	if window <= 0. || previous.abs() >= MATE_SCORE {
+		window = full;
+	}
+	loop {
+		let alpha = f32::max(previous - window, -full);
+		let beta = f32::min(previous + window, full);
+		let rate = -minimax_rate_state(
+			stats,
+			state,
+			-player_index,
+			-beta,
+			-alpha,
+			depth,
+			tt,
+			ordering,
+			Some(*root_move),
+			clock,
+			params,
+			evaluator,
+		);
+		if rate.is_nan() || window >= full {
+			return rate;
+		}
+		stats.aspire_probed += 1;
+		if alpha < rate && rate < beta {
+			return rate;
+		}
+		stats.aspire_re += 1;
+		if params.aspiration_growth > 1. {
+			window *= params.aspiration_growth;
+		} else {
+			window = full;
+		}
+	}
+}
+
+/// Searches a later root move. With PVS it only has to be proven worse than `best` with a
+/// null window and is searched again with the full window if that fails
+fn principal_variation_search(
+	stats: &mut SearchStatistics,
+	state: &GameState,
+	player_index: i8,
+	best: f32,
+	depth: u8,
+	tt: &mut TranspositionTable,
+	ordering: &mut MoveOrdering,
+	root_move: &Move,
+	clock: &SearchClock,
+	params: &MinimaxParameters,
+	evaluator: &dyn Evaluator,
+) -> f32 {
+	if params.pvs {
+		let rate = -minimax_rate_state(
+			stats,
+			state,
+			-player_index,
+			-best - 1e-5,
+			-best,
+			depth,
+			tt,
+			ordering,
+			Some(*root_move),
+			clock,
+			params,
+			evaluator,
+		);
+		stats.probed += 1;
+		if rate.is_nan() || rate <= best {
+			return rate;
+		}
+		stats.re_searched += 1;
+	}
+	return -minimax_rate_state(
+		stats,
+		state,
+		-player_index,
+		-MATE_SCORE - MAX_MATE_PENALTY,
+		MATE_SCORE + MAX_MATE_PENALTY,
+		depth,
+		tt,
+		ordering,
+		Some(*root_move),
+		clock,
+		params,
+		evaluator,
+	);
 }
 
 impl MinimaxPlayer {
 	pub fn move_with_id(&mut self, state: &GameState, id: i64) -> Move {
-		// self.tt = TranspositionTable::new();
-		let before = time::now();
+		return self.move_since(state, id, time::now());
+	}
+
+	/// Like `move_with_id` for a move request that arrived at `start`, the time spent on it
+	/// before counts against the limit
+	pub fn move_since(&mut self, state: &GameState, id: i64, start: time::Tm) -> Move {
+		if let Some(ref book) = self.book {
+			if let Some(action) = book.lookup(state, self.book_randomness, &mut self.rng) {
+				return action;
+			}
+		}
+		let clock = SearchClock::started_at(start, self.limit);
+		self.tt.new_search();
+		self.ordering.new_search();
 		let player_index = match state.get_current_player_color() {
 			PlayerColor::Red => 1,
 			PlayerColor::Blue => -1,
@@ -39,6 +276,8 @@ impl MinimaxPlayer {
 
 		let mut playable_moves = state.get_move_list();
 		let mut action = None;
+		let mut rated_moves = Vec::new();
+		let mut current_rated_moves = Vec::new();
 		let mut best = -MATE_SCORE - MAX_MATE_PENALTY;
 
 		let mut current_index = 0;
@@ -48,7 +287,7 @@ impl MinimaxPlayer {
 		let color = state.get_current_player_color();
 		let mut search_stats = SearchStatistics::new();
 		let mut last_nodes = 0.;
-		let mut branching_factor = f32::NAN;
+		let mut branching_factor = 0.;
 		let start_nodes = playable_moves.len() as f32;
 
 		let hash = MinimalState::from_state(state);
@@ -71,7 +310,7 @@ impl MinimaxPlayer {
 			}
 		}
 
-		while (time::now() - before).num_milliseconds() < 1700 {
+		while !clock.expired(search_stats.nodes) {
 			search_stats.nodes += 1;
 			if current_depth == START_DEPTH {
 				let action_considered = playable_moves
@@ -87,7 +326,11 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth,
 					&mut self.tt,
-					&before,
+					&mut self.ordering,
+					Some(*action_considered),
+					&clock,
+					&self.params,
+					&*self.evaluator,
 				);
 				if rate.is_nan() {
 					break;
@@ -97,99 +340,58 @@ impl MinimaxPlayer {
 					current_depth_best = rate;
 					current_depth_best_move = Some(action_considered.clone());
 				}
+				current_rated_moves.push((action_considered.clone(), rate));
 			} else if current_index == 0 {
 				let action_considered = playable_moves
 					.get(current_index)
 					.expect("Wrong index for move list");
 				let mut state = state.clone();
 				state.perform(&action_considered, &color);
-				current_depth_best = -minimax_rate_state(
+				current_depth_best = aspiration_search(
 					&mut search_stats,
 					&state,
-					-player_index,
-					-MATE_SCORE - MAX_MATE_PENALTY,
-					MATE_SCORE + MAX_MATE_PENALTY,
+					player_index,
+					best,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
+					&mut self.ordering,
+					action_considered,
+					&clock,
+					&self.params,
+					&*self.evaluator,
 				);
 				if current_depth_best.is_nan() {
 					break;
 				}
 				current_depth_best_move = Some(*action_considered);
-			/*let mut rate = -minimax_rate_state(
-				&mut search_stats,
-				&state,
-				-player_index,
-				-best - WINDOW_SIZE,
-				-best + WINDOW_SIZE,
-				current_depth,
-				&mut self.tt,
-				&before,
-			);
-			if rate.is_nan() {
-				break;
-			}
-			search_stats.aspire_probed += 1;
-			if rate >= best + WINDOW_SIZE || rate <= best - WINDOW_SIZE {
-				search_stats.aspire_re += 1;
-				rate = -minimax_rate_state(
-					&mut search_stats,
-					&state,
-					-player_index,
-					-MATE_SCORE - MAX_MATE_PENALTY,
-					MATE_SCORE + MAX_MATE_PENALTY,
-					current_depth - 1,
-					&mut self.tt,
-					&before,
-				);
-				if rate.is_nan() {
-					break;
-				}
-			}
-			current_depth_best = rate;
-			current_depth_best_move = Some(action_considered.clone());*/
+				current_rated_moves.push((*action_considered, current_depth_best));
 			} else {
 				let action_considered = playable_moves
 					.get(current_index)
 					.expect("Wrong index for move list");
 				let mut state = state.clone();
 				state.perform(&action_considered, &color);
-				let mut rate;
-				rate = -minimax_rate_state(
+				let rate = principal_variation_search(
 					&mut search_stats,
 					&state,
-					-player_index,
-					-current_depth_best - 1e-5,
-					-current_depth_best,
+					player_index,
+					current_depth_best,
 					current_depth - 1,
 					&mut self.tt,
-					&before,
+					&mut self.ordering,
+					action_considered,
+					&clock,
+					&self.params,
+					&*self.evaluator,
 				);
-				if rate == f32::NAN {
+				if rate.is_nan() {
 					break;
 				}
-				search_stats.probed += 1;
 				if rate > current_depth_best {
-					search_stats.re_searched += 1;
-					rate = -minimax_rate_state(
-						&mut search_stats,
-						&state,
-						-player_index,
-						-MATE_SCORE - MAX_MATE_PENALTY,
-						MATE_SCORE + MAX_MATE_PENALTY,
-						current_depth - 1,
-						&mut self.tt,
-						&before,
-					);
-					if rate == f32::NAN {
-						break;
-					}
-					if rate >= current_depth_best {
-						current_depth_best = rate;
-						current_depth_best_move = Some(*action_considered);
-					}
+					current_depth_best = rate;
+					current_depth_best_move = Some(*action_considered);
 				}
+				current_rated_moves.push((*action_considered, rate));
 			}
 
 			current_index += 1;
@@ -208,10 +410,14 @@ impl MinimaxPlayer {
 
 				current_depth_best = -MATE_SCORE - MAX_MATE_PENALTY;
 				current_depth_best_move = None;
+				rated_moves = current_rated_moves;
+				current_rated_moves = Vec::new();
 				branching_factor =
-					(search_stats.nodes as f32 / start_nodes).powf(1. / (current_depth as f32));
-				branching_factor += search_stats.nodes as f32 / last_nodes;
-				branching_factor = branching_factor / 2.;
+					ratio(search_stats.nodes as f32, start_nodes).powf(1. / (current_depth as f32));
+				if last_nodes > 0. {
+					branching_factor += search_stats.nodes as f32 / last_nodes;
+					branching_factor = branching_factor / 2.;
+				}
 				last_nodes = search_stats.nodes as f32;
 				if (best <= -MATE_SCORE || best >= MATE_SCORE) && action != None {
 					break;
@@ -221,6 +427,8 @@ impl MinimaxPlayer {
 		if current_depth_best_move != action {
 			if current_depth_best > -MATE_SCORE {
 				action = current_depth_best_move;
+				best = current_depth_best;
+				rated_moves = current_rated_moves;
 				current_depth += 1;
 			}
 		}
@@ -229,24 +437,76 @@ impl MinimaxPlayer {
 			id: id as u32,
 			gamestate: state.clone(),
 			moves,
-			data: MinimaxPlayer::get_data_vec(&state),
+			data: FeatureVector::data_vec(&state),
 		};
 		if let Some(ref tx) = self.tx {
 			tx.send(Data::Step(send_state)).unwrap();
-		} else if id < 0 {
-			let ms_used = (time::now() - before).num_milliseconds();
-			println!(
-				"|{}| {}ms | {} nodes | val {:.3} | re-rate {:.5}/{:.2} | depth {} | {:.0} nps | b {:.3}",
-				state.turn,
-				ms_used,
-				search_stats.nodes,
-				best,
-				search_stats.re_searched as f32 / search_stats.probed as f32,
-				search_stats.aspire_re as f32 / search_stats.aspire_probed as f32,
-				current_depth - 1,
-				search_stats.nodes as f32 / (ms_used as f32 / 1000.),
-				branching_factor,
-			);
+		}
+		if self.tx.is_some() || id < 0 {
+			let ms_used = clock.elapsed_ms();
+			let stats = vec![
+				("q_nodes".to_string(), search_stats.q_nodes as f32),
+				(
+					"re_searched".to_string(),
+					ratio(search_stats.re_searched as f32, search_stats.probed as f32),
+				),
+				(
+					"aspiration_re_searched".to_string(),
+					ratio(
+						search_stats.aspire_re as f32,
+						search_stats.aspire_probed as f32,
+					),
+				),
+				(
+					"nps".to_string(),
+					ratio(search_stats.nodes as f32, ms_used as f32 / 1000.),
+				),
+				("branching_factor".to_string(), branching_factor),
+				("tt_hit_rate".to_string(), self.tt.stats.hit_rate()),
+				(
+					"tt_collision_rate".to_string(),
+					self.tt.stats.collision_rate(),
+				),
+				("tt_hashfull".to_string(), self.tt.hashfull() as f32),
+				(
+					"first_move_cutoff_rate".to_string(),
+					self.ordering.stats.first_move_cutoff_rate(),
+				),
+				(
+					"null_cutoffs".to_string(),
+					search_stats.pruning.null_cutoffs as f32,
+				),
+				(
+					"null_tried".to_string(),
+					search_stats.pruning.null_tried as f32,
+				),
+				(
+					"reduced_re_searched".to_string(),
+					search_stats.pruning.reduced_re_searched as f32,
+				),
+				("reduced".to_string(), search_stats.pruning.reduced as f32),
+				("futile".to_string(), search_stats.pruning.futile as f32),
+				(
+					"probe_cuts".to_string(),
+					search_stats.pruning.probe_cuts as f32,
+				),
+			];
+			let action = action.or(current_depth_best_move);
+			let report = SearchReport {
+				engine: "minimax".to_string(),
+				id,
+				turn: state.turn,
+				time_ms: ms_used,
+				action,
+				score: best,
+				depth: current_depth.checked_sub(1),
+				nodes: search_stats.nodes as u64,
+				proven: None,
+				pv: action.map_or(Vec::new(), |action| line_after(&self.tt, state, action)),
+				moves: rated_root_moves(&self.tt, state, &rated_moves),
+				stats,
+			};
+			send_report(&self.tx, report);
 		}
 		if let Some(action) = action {
 			return action;
@@ -259,8 +519,10 @@ impl MinimaxPlayer {
 	}
 
 	pub fn get_move_and_rate(state: &GameState, max_depth: u8) -> (Move, f32, Vec<(Move, f32)>) {
-		let mut tt = TranspositionTable::new();
-		let before = time::now();
+		let mut tt = TranspositionTable::new(DEFAULT_SIZE_MB);
+		let mut ordering = MoveOrdering::new();
+		let params = DEFAULT_PARAMETERS;
+		let clock = SearchClock::new(DEFAULT_LIMIT);
 		let player_index = match state.get_current_player_color() {
 			PlayerColor::Red => 1,
 			PlayerColor::Blue => -1,
@@ -295,9 +557,13 @@ impl MinimaxPlayer {
 					MATE_SCORE + MAX_MATE_PENALTY,
 					current_depth,
 					&mut tt,
-					&before,
+					&mut ordering,
+					Some(*action_considered),
+					&clock,
+					&params,
+					&ClopEvaluator,
 				);
-				if rate == f32::NAN {
+				if rate.is_nan() {
 					break;
 				}
 
@@ -312,95 +578,51 @@ impl MinimaxPlayer {
 					.expect("Wrong index for move list");
 				let mut state = state.clone();
 				state.perform(&action_considered, &color);
-				current_depth_best = -minimax_rate_state(
+				current_depth_best = aspiration_search(
 					&mut search_stats,
 					&state,
-					-player_index,
-					-MATE_SCORE - MAX_MATE_PENALTY,
-					MATE_SCORE + MAX_MATE_PENALTY,
+					player_index,
+					best,
 					current_depth - 1,
 					&mut tt,
-					&before,
-				);
-				if current_depth_best == f32::NAN {
-					break;
-				}
-				current_depth_best_move = Some(action_considered.clone());
-				current_rated_moves.push((action_considered.clone(), current_depth_best));
-			/*let mut rate = -minimax_rate_state(
-				&mut search_stats,
-				&state,
-				-player_index,
-				-best - WINDOW_SIZE,
-				-best + WINDOW_SIZE,
-				current_depth,
-				&mut self.tt,
-				&before,
-			);
-			if rate == f32::NAN {
-				break;
-			}
-			search_stats.aspire_probed += 1;
-			if rate >= best + WINDOW_SIZE || rate <= best - WINDOW_SIZE {
-				search_stats.aspire_re += 1;
-				rate = -minimax_rate_state(
-					&mut search_stats,
-					&state,
-					-player_index,
-					-MATE_SCORE - MAX_MATE_PENALTY,
-					MATE_SCORE + MAX_MATE_PENALTY,
-					current_depth - 1,
-					&mut self.tt,
-					&before,
+					&mut ordering,
+					action_considered,
+					&clock,
+					&params,
+					&ClopEvaluator,
 				);
-				if rate == f32::NAN {
+				if current_depth_best.is_nan() {
 					break;
 				}
-			}
-			current_depth_best = rate;
-			current_depth_best_move = Some(action_considered.clone());*/
+				current_depth_best_move = Some(*action_considered);
+				current_rated_moves.push((*action_considered, current_depth_best));
 			} else {
 				let action_considered = playable_moves
 					.get(current_index)
 					.expect("Wrong index for move list");
 				let mut state = state.clone();
 				state.perform(&action_considered, &color);
-				let mut rate;
-				rate = -minimax_rate_state(
+				let rate = principal_variation_search(
 					&mut search_stats,
 					&state,
-					-player_index,
-					-current_depth_best - 1e-5,
-					-current_depth_best,
+					player_index,
+					current_depth_best,
 					current_depth - 1,
 					&mut tt,
-					&before,
+					&mut ordering,
+					action_considered,
+					&clock,
+					&params,
+					&ClopEvaluator,
 				);
 				if rate.is_nan() {
 					break;
 				}
-				search_stats.probed += 1;
 				if rate > current_depth_best {
-					search_stats.re_searched += 1;
-					rate = -minimax_rate_state(
-						&mut search_stats,
-						&state,
-						-player_index,
-						-MATE_SCORE - MAX_MATE_PENALTY,
-						MATE_SCORE + MAX_MATE_PENALTY,
-						current_depth - 1,
-						&mut tt,
-						&before,
-					);
-					if rate.is_nan() {
-						break;
-					}
-					if rate >= current_depth_best {
-						current_depth_best = rate;
-						current_depth_best_move = Some(action_considered.clone());
-					}
+					current_depth_best = rate;
+					current_depth_best_move = Some(*action_considered);
 				}
-				current_rated_moves.push((action_considered.clone(), rate));
+				current_rated_moves.push((*action_considered, rate));
 			}
 
 			current_index += 1;
@@ -445,71 +667,6 @@ impl MinimaxPlayer {
 			panic!("No playable move found");
 		}
 	}
-
-	fn get_data_vec(state: &GameState) -> Vec<(String, f32)> {
-		let mut result = MinimaxPlayer::get_colored_data(state, &PlayerColor::Red);
-		result.extend(MinimaxPlayer::get_colored_data(state, &PlayerColor::Blue));
-		return result;
-	}
-
-	fn add_to_result(
-		result: &mut Vec<(String, f32)>,
-		color: &PlayerColor,
-		value: f32,
-		description: &str,
-	) {
-		let string = format!("{}-{}", color, description).to_string();
-		result.push((string, value));
-	}
-
-	fn get_colored_data(state: &GameState, color: &PlayerColor) -> Vec<(String, f32)> {
-		let mut result = Vec::new();
-		let swarm = Helper::greatest_swarm_new(state, color);
-		let mut win: f32 = 0.;
-		let mut len = 0;
-		for (x, y) in state.get_own_fields(color) {
-			win += 1. - (Helper::get_distance_to_swarm_new(x, y, &swarm) as f32 / 9.);
-			len += 1;
-		}
-		MinimaxPlayer::add_to_result(&mut result, color, win, "DISTANCES");
-		win = win / len as f32;
-		MinimaxPlayer::add_to_result(&mut result, color, win, "ADJ_DISTANCES");
-		MinimaxPlayer::add_to_result(&mut result, color, swarm.count_ones() as f32, "SWARM_COUNT");
-		MinimaxPlayer::add_to_result(
-			&mut result,
-			color,
-			swarm.count_ones() as f32 / 16.,
-			"ADJ_SWARM_COUNT",
-		);
-
-		let fishes = state.board.get_fields_of(color);
-
-		let mut center = fishes;
-		center.mask(297799908644072875622400);
-		let center_count = center.count_ones() as f32;
-		MinimaxPlayer::add_to_result(&mut result, color, center_count, "CENTER_COUNT");
-		MinimaxPlayer::add_to_result(
-			&mut result,
-			color,
-			center_count / len as f32,
-			"ADJ_CENTER_COUNT",
-		);
-
-		let mut border = fishes;
-		border.mask(1267033445369934637136782821375);
-		let border_count = border.count_ones() as f32;
-		MinimaxPlayer::add_to_result(&mut result, color, border_count, "BORDER_COUNT");
-		MinimaxPlayer::add_to_result(
-			&mut result,
-			color,
-			border_count / len as f32,
-			"ADJ_BORDER_COUNT",
-		);
-
-		MinimaxPlayer::add_to_result(&mut result, color, len as f32, "COUNT");
-		MinimaxPlayer::add_to_result(&mut result, color, len as f32 / 16., "ADJ_COUNT");
-		return result;
-	}
 }
 
 impl ClientListener for MinimaxPlayer {
@@ -518,3 +675,51 @@ impl ClientListener for MinimaxPlayer {
 		return self.move_with_id(state, id);
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use game_sdk::gamerules;
+
+	#[test]
+	fn node_limit_repeats_the_moves() {
+		let play = || {
+			let mut player = MinimaxPlayer::new(None, 0);
+			player.set_limit(SearchLimit::Nodes(3000));
+			player.set_seed(5);
+			let mut state = gamerules::get_seeded_state(11);
+			let mut moves = Vec::new();
+			for _ in 0..4 {
+				let action = player.on_move_request(&state);
+				let color = state.get_current_player_color();
+				state.perform(&action, &color);
+				moves.push(action);
+			}
+			return moves;
+		};
+		assert_eq!(play(), play());
+	}
+
+	#[test]
+	fn reports_without_aspiration_round_trip() {
+		let (tx, rx) = mpsc::channel();
+		let params = MinimaxParameters {
+			aspiration_window: 0.,
+			..DEFAULT_PARAMETERS
+		};
+		let mut player = MinimaxPlayer::with_parameters(Some(tx), 0, params);
+		player.set_limit(SearchLimit::Nodes(500));
+		player.on_move_request(&gamerules::get_seeded_state(3));
+		let report = rx
+			.try_iter()
+			.filter_map(|data| match data {
+				Data::Report(report) => Some(report),
+				_ => None,
+			})
+			.next()
+			.expect("No report sent");
+		assert!(report.stats.iter().all(|&(_, value)| value.is_finite()));
+		let json = report.to_json();
+		assert_eq!(SearchReport::from_json(&json).map(|report| report.to_json()), Ok(json));
+	}
+}
@@ -0,0 +1,209 @@
+use game_sdk::{gamerules, GameState, Move, PlayerColor};
+use rand::rngs::StdRng;
+use rand::Rng;
+
+use crate::eval::{Evaluator, TexelEvaluator, DEFAULT_TEXEL};
+use crate::LogicBasedPlayer;
+
+use std::sync::Arc;
+
+/// How the MCTS players play a game to its end after adding a node.
+///
+/// Playouts are run from many threads of a simulation at once, so policies hold no mutable
+/// state. Random choices are drawn from the generator of the search, which makes seeded
+/// searches repeatable.
+pub trait PlayoutPolicy: Send + Sync {
+    /// Move to play in `state`, `None` if the side to move has none
+    fn next_move(&self, state: &GameState, rng: &mut StdRng) -> Option<Move>;
+
+    /// Chance of `color` winning `state`, reached after `plies` playout moves. `None` lets the
+    /// playout go on
+    fn estimate(&self, _state: &GameState, _plies: u8, _color: &PlayerColor) -> Option<f32> {
+        return None;
+    }
+}
+
+/// Always the move leaving the lowest variance, what the RAVE players always played
+#[derive(Clone, Copy, Debug)]
+pub struct GreedyPlayout;
+
+impl PlayoutPolicy for GreedyPlayout {
+    fn next_move(&self, state: &GameState, _rng: &mut StdRng) -> Option<Move> {
+        return LogicBasedPlayer::on_state(state);
+    }
+}
+
+/// Any legal move with the same probability
+#[derive(Clone, Copy, Debug)]
+pub struct RandomPlayout;
+
+impl PlayoutPolicy for RandomPlayout {
+    fn next_move(&self, state: &GameState, rng: &mut StdRng) -> Option<Move> {
+        let moves = state.get_move_list();
+        if moves.is_empty() {
+            return None;
+        }
+        return Some(moves[rng.gen_range(0, moves.len())]);
+    }
+}
+
+/// A random move with probability `epsilon`, the greedy one otherwise
+#[derive(Clone, Copy, Debug)]
+pub struct EpsilonGreedyPlayout {
+    pub epsilon: f32,
+}
+
+impl PlayoutPolicy for EpsilonGreedyPlayout {
+    fn next_move(&self, state: &GameState, rng: &mut StdRng) -> Option<Move> {
+        let mut rated = LogicBasedPlayer::get_rated_moves(state);
+        if rated.is_empty() {
+            return None;
+        }
+        if rng.gen::<f32>() < self.epsilon {
+            let index = rng.gen_range(0, rated.len());
+            return Some(rated.swap_remove(index).1);
+        }
+        return rated
+            .into_iter()
+            .min_by_key(|&(rate, _)| rate)
+            .map(|(_, action)| action);
+    }
+}
+
+/// Moves drawn with a softmax over the variance they leave, `temperature` is given in units of
+/// variance. Low temperatures approach the greedy playout, high ones the random one
+#[derive(Clone, Copy, Debug)]
+pub struct SoftmaxPlayout {
+    pub temperature: f32,
+}
+
+impl PlayoutPolicy for SoftmaxPlayout {
+    fn next_move(&self, state: &GameState, rng: &mut StdRng) -> Option<Move> {
+        let rated = LogicBasedPlayer::get_rated_moves(state);
+        let best = rated.iter().map(|&(rate, _)| rate).min()?;
+        let weights: Vec<f32> = rated
+            .iter()
+            .map(|&(rate, _)| (-((rate - best) as f32) / self.temperature).exp())
+            .collect();
+        let mut pick = rng.gen::<f32>() * weights.iter().sum::<f32>();
+        for (&(_, action), weight) in rated.iter().zip(weights.iter()) {
+            if pick < *weight {
+                return Some(action);
+            }
+            pick -= weight;
+        }
+        return rated.last().map(|&(_, action)| action);
+    }
+}
+
+/// Plays `inner` for `plies` moves, then stops and rates the position with `evaluator`.
+/// The evaluation is turned into a winning chance with a logistic function, `scale` being its
+/// steepness like the scaling of the texel tuner
+#[derive(Clone)]
+pub struct CutoffPlayout {
+    pub inner: Arc<dyn PlayoutPolicy>,
+    pub evaluator: Arc<dyn Evaluator>,
+    pub plies: u8,
+    pub scale: f32,
+}
+
+impl CutoffPlayout {
+    /// Greedy moves rated by the default texel weights after `plies`
+    pub fn new(plies: u8) -> CutoffPlayout {
+        return CutoffPlayout {
+            inner: Arc::new(GreedyPlayout),
+            evaluator: Arc::new(TexelEvaluator::new(DEFAULT_TEXEL)),
+            plies,
+            scale: 1.,
+        };
+    }
+}
+
+impl PlayoutPolicy for CutoffPlayout {
+    fn next_move(&self, state: &GameState, rng: &mut StdRng) -> Option<Move> {
+        return self.inner.next_move(state, rng);
+    }
+
+    fn estimate(&self, state: &GameState, plies: u8, color: &PlayerColor) -> Option<f32> {
+        // the evaluation of an odd turn misses the answer of blue
+        if plies < self.plies || state.turn % 2 == 1 || gamerules::is_finished(state) {
+            return self.inner.estimate(state, plies, color);
+        }
+        let red = 1. / (1. + (-self.scale * self.evaluator.evaluate(state)).exp());
+        return Some(match color {
+            PlayerColor::Red => red,
+            PlayerColor::Blue => 1. - red,
+        });
+    }
+}
+
+/// Reads a policy written as `greedy`, `random`, `epsilon=<epsilon>`,
+/// `softmax=<temperature>` or `cutoff=<plies>`
+pub fn playout_from_string(text: &str) -> Result<Arc<dyn PlayoutPolicy>, String> {
+    let mut parts = text.splitn(2, '=');
+    let kind = parts.next().unwrap_or("");
+    let value = parts.next();
+    let number_error = || format!("{:?} is no valid value for {}", value, kind);
+    return match (kind, value) {
+        ("greedy", None) => Ok(Arc::new(GreedyPlayout)),
+        ("random", None) => Ok(Arc::new(RandomPlayout)),
+        ("epsilon", Some(value)) => Ok(Arc::new(EpsilonGreedyPlayout {
+            epsilon: value.parse().map_err(|_| number_error())?,
+        })),
+        ("softmax", Some(value)) => Ok(Arc::new(SoftmaxPlayout {
+            temperature: value.parse().map_err(|_| number_error())?,
+        })),
+        ("cutoff", Some(value)) => Ok(Arc::new(CutoffPlayout::new(
+            value.parse().map_err(|_| number_error())?,
+        ))),
+        _ => Err(format!("Unknown playout {}", text)),
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::limit::engine_rng;
+
+    #[test]
+    fn policies_play_legal_moves() {
+        let state = gamerules::get_seeded_state(1);
+        let mut rng = engine_rng(Some(1));
+        let moves = state.get_move_list();
+        for text in &["greedy", "random", "epsilon=0.5", "softmax=50", "cutoff=4"] {
+            let policy = playout_from_string(text).expect("Could not parse playout");
+            let action = policy.next_move(&state, &mut rng).expect("No playout move");
+            assert!(moves.contains(&action), "{} played an illegal move", text);
+        }
+        let rated = LogicBasedPlayer::get_rated_moves(&state);
+        let best = rated.iter().map(|&(rate, _)| rate).min();
+        let cold = SoftmaxPlayout { temperature: 1e-6 }.next_move(&state, &mut rng);
+        assert_eq!(
+            rated
+                .iter()
+                .find(|&&(_, action)| Some(action) == cold)
+                .map(|&(rate, _)| rate),
+            best
+        );
+        assert!(playout_from_string("softmax").is_err());
+        assert!(playout_from_string("epsilon=some").is_err());
+        assert!(playout_from_string("uniform").is_err());
+    }
+
+    #[test]
+    fn cutoff_rates_even_turns() {
+        let mut state = gamerules::get_seeded_state(2);
+        let policy = CutoffPlayout::new(2);
+        assert_eq!(policy.estimate(&state, 0, &PlayerColor::Red), None);
+        state.turn = 2;
+        assert_eq!(policy.estimate(&state, 1, &PlayerColor::Red), None);
+        let red = policy
+            .estimate(&state, 2, &PlayerColor::Red)
+            .expect("No estimate after the cutoff");
+        let blue = policy
+            .estimate(&state, 2, &PlayerColor::Blue)
+            .expect("No estimate after the cutoff");
+        assert!(red > 0. && red < 1.);
+        assert!((red + blue - 1.).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,8 @@
+//! Building blocks shared by the alpha-beta players (`minimax`, `legacy_minimax`, `clop_player`)
+
+pub mod ordering;
+pub mod pruning;
+pub mod quiescence;
+pub mod report;
+pub mod solver;
+pub mod transposition;
@@ -0,0 +1,176 @@
+use game_sdk::{GameState, Move, PlayerColor};
+
+use super::report::ratio;
+
+/// One more than the last turn of a game, killers are stored per turn
+const MAX_TURNS: usize = 61;
+const FIELDS: usize = 100;
+/// History scores are halved once one of them grows beyond this
+const HISTORY_LIMIT: i32 = 1 << 24;
+
+const KILLER_SCORE: i32 = 1 << 30;
+const COUNTER_SCORE: i32 = 1 << 29;
+
+/// Counters since the last call to `new_search`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OrderingStatistics {
+    pub cutoffs: usize,
+    /// cutoffs caused by the first move searched in a node
+    pub first_move_cutoffs: usize,
+}
+
+impl OrderingStatistics {
+    pub fn first_move_cutoff_rate(&self) -> f32 {
+        return ratio(self.first_move_cutoffs as f32, self.cutoffs as f32);
+    }
+}
+
+/// Move-ordering heuristics for the alpha-beta players.
+///
+/// Moves are sorted by two killer moves per turn, the countermove to the opponent's last move
+/// and a history score per color, origin and destination. The TT move is left to the caller,
+/// it always goes first.
+#[derive(Clone)]
+pub struct MoveOrdering {
+    killers: Vec<[Option<Move>; 2]>,
+    history: Vec<i32>,
+    countermoves: Vec<Option<Move>>,
+    pub stats: OrderingStatistics,
+}
+
+fn from_to(action: &Move) -> usize {
+    let from = action.x as usize + action.y as usize * 10;
+    let to = action.dest_x as usize + action.dest_y as usize * 10;
+    return from * FIELDS + to;
+}
+
+fn history_index(color: &PlayerColor, action: &Move) -> usize {
+    return *color as usize * FIELDS * FIELDS + from_to(action);
+}
+
+impl MoveOrdering {
+    pub fn new() -> MoveOrdering {
+        return MoveOrdering {
+            killers: vec![[None; 2]; MAX_TURNS],
+            history: vec![0; 2 * FIELDS * FIELDS],
+            countermoves: vec![None; FIELDS * FIELDS],
+            stats: OrderingStatistics::default(),
+        };
+    }
+
+    /// Must be called once before every search from a new root. Killers are dropped, history
+    /// is only faded so the next search can still profit from it
+    pub fn new_search(&mut self) {
+        for killers in self.killers.iter_mut() {
+            *killers = [None; 2];
+        }
+        for score in self.history.iter_mut() {
+            *score /= 2;
+        }
+        self.stats = OrderingStatistics::default();
+    }
+
+    fn score(
+        &self,
+        action: &Move,
+        turn: usize,
+        color: &PlayerColor,
+        counter: &Option<Move>,
+    ) -> i32 {
+        let killers = &self.killers[turn];
+        if killers[0] == Some(*action) {
+            return KILLER_SCORE;
+        }
+        if killers[1] == Some(*action) {
+            return KILLER_SCORE - 1;
+        }
+        if *counter == Some(*action) {
+            return COUNTER_SCORE;
+        }
+        return self.history[history_index(color, action)];
+    }
+
+    /// Sorts `moves` of `state` best first, `previous` being the move that led to `state`
+    pub fn order(&self, moves: &mut [Move], state: &GameState, previous: Option<Move>) {
+        let turn = usize::min(state.turn as usize, MAX_TURNS - 1);
+        let color = state.get_current_player_color();
+        let counter = match previous {
+            Some(previous) => self.countermoves[from_to(&previous)],
+            None => None,
+        };
+        let mut scored: Vec<(i32, Move)> = moves
+            .iter()
+            .map(|action| (self.score(action, turn, &color, &counter), *action))
+            .collect();
+        scored.sort_by_key(|&(score, _)| -score);
+        for (i, (_, action)) in scored.into_iter().enumerate() {
+            moves[i] = action;
+        }
+    }
+
+    /// Rewards `action` for failing high in `state` after it was tried as `index`th move
+    pub fn cutoff(
+        &mut self,
+        action: &Move,
+        state: &GameState,
+        previous: Option<Move>,
+        depth: u8,
+        index: usize,
+    ) {
+        self.stats.cutoffs += 1;
+        if index == 0 {
+            self.stats.first_move_cutoffs += 1;
+        }
+        let turn = usize::min(state.turn as usize, MAX_TURNS - 1);
+        let killers = &mut self.killers[turn];
+        if killers[0] != Some(*action) {
+            killers[1] = killers[0];
+            killers[0] = Some(*action);
+        }
+        if let Some(previous) = previous {
+            self.countermoves[from_to(&previous)] = Some(*action);
+        }
+        let index = history_index(&state.get_current_player_color(), action);
+        self.history[index] += depth as i32 * depth as i32;
+        if self.history[index] > HISTORY_LIMIT {
+            for score in self.history.iter_mut() {
+                *score /= 2;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::gamerules;
+
+    #[test]
+    fn killers_first() {
+        let mut ordering = MoveOrdering::new();
+        let state = gamerules::get_seeded_state(1);
+        let mut moves = state.get_move_list();
+        let killer = moves[moves.len() - 1];
+        let historic = moves[moves.len() - 2];
+        ordering.cutoff(&historic, &state, None, 10, 3);
+        ordering.cutoff(&killer, &state, None, 1, 0);
+        ordering.order(&mut moves, &state, None);
+        assert!(moves[0] == killer);
+        assert!(moves[1] == historic);
+        assert_eq!(ordering.stats.first_move_cutoff_rate(), 0.5);
+    }
+
+    #[test]
+    fn countermove_after_previous() {
+        let mut ordering = MoveOrdering::new();
+        let state = gamerules::get_seeded_state(2);
+        let mut moves = state.get_move_list();
+        let previous = Move::new(0, 0, 1, 1, game_sdk::Direction::UpRight);
+        let counter = moves[moves.len() - 1];
+        ordering.cutoff(&counter, &state, Some(previous), 1, 1);
+        ordering.new_search();
+        ordering.order(&mut moves, &state, Some(previous));
+        assert!(moves[0] == counter);
+        assert_eq!(ordering.stats.cutoffs, 0);
+    }
+}
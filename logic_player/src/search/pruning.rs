@@ -0,0 +1,180 @@
+use game_sdk::{GameState, Move};
+
+/// Null moves are not tried this close to the turn limit, where passing changes who moves last
+const NULL_MOVE_LAST_TURN: u8 = 56;
+
+#[derive(Clone, Copy, Debug)]
+pub struct PruningParameters {
+    pub null_move: bool,
+    /// plies the null move search is shallower than the regular one
+    pub null_move_reduction: u8,
+    /// zugzwang guard, side to move needs at least this many fishes to pass
+    pub null_move_min_fishes: u8,
+    pub lmr: bool,
+    pub lmr_min_depth: u8,
+    /// moves of a node searched at full depth before reductions start
+    pub lmr_full_moves: usize,
+    pub lmr_reduction: u8,
+    pub futility: bool,
+    /// margin per remaining ply a quiet move has to stay within to still be searched
+    pub futility_margin: f32,
+    pub futility_max_depth: u8,
+    /// shallow null window searches of later moves, the original forward pruning of the
+    /// minimax player: moves rated below alpha by the static evaluation are skipped if a
+    /// search two plies shallower agrees, ones far above beta cut off the node
+    pub probes: bool,
+    pub probe_margin: f32,
+}
+
+pub const DEFAULT_PRUNING: PruningParameters = PruningParameters {
+    null_move: false,
+    null_move_reduction: 2,
+    null_move_min_fishes: 5,
+    lmr: false,
+    lmr_min_depth: 3,
+    lmr_full_moves: 4,
+    lmr_reduction: 1,
+    futility: false,
+    futility_margin: 38.641,
+    futility_max_depth: 2,
+    probes: true,
+    probe_margin: 38.641,
+};
+
+/// Counters for how often each selective feature fired
+#[derive(Clone, Copy, Debug, Default)]
+pub struct PruningStatistics {
+    pub null_tried: usize,
+    pub null_cutoffs: usize,
+    pub reduced: usize,
+    /// reduced searches that beat alpha and had to be repeated at full depth
+    pub reduced_re_searched: usize,
+    pub futile: usize,
+    /// moves skipped or nodes cut off after a shallow probe
+    pub probe_cuts: usize,
+}
+
+impl PruningParameters {
+    pub fn empty() -> PruningParameters {
+        return PruningParameters {
+            null_move: false,
+            null_move_reduction: 0,
+            null_move_min_fishes: 0,
+            lmr: false,
+            lmr_min_depth: 0,
+            lmr_full_moves: 0,
+            lmr_reduction: 0,
+            futility: false,
+            futility_margin: 0.0,
+            futility_max_depth: 0,
+            probes: false,
+            probe_margin: 0.0,
+        };
+    }
+
+    /// Handles the pruning keys of the engines' `set_var_from_string`, false if it is none
+    pub fn set_var_from_string(&mut self, identifier: &str, val: &str) -> bool {
+        match identifier {
+            "null_move" => self.null_move = val.parse().expect("Got wrong val"),
+            "null_move_reduction" => self.null_move_reduction = val.parse().expect("Got wrong val"),
+            "null_move_min_fishes" => {
+                self.null_move_min_fishes = val.parse().expect("Got wrong val")
+            }
+            "lmr" => self.lmr = val.parse().expect("Got wrong val"),
+            "lmr_min_depth" => self.lmr_min_depth = val.parse().expect("Got wrong val"),
+            "lmr_full_moves" => self.lmr_full_moves = val.parse().expect("Got wrong val"),
+            "lmr_reduction" => self.lmr_reduction = val.parse().expect("Got wrong val"),
+            "futility" => self.futility = val.parse().expect("Got wrong val"),
+            "futility_margin" => self.futility_margin = val.parse().expect("Got wrong val"),
+            "futility_max_depth" => self.futility_max_depth = val.parse().expect("Got wrong val"),
+            "probes" => self.probes = val.parse().expect("Got wrong val"),
+            "probe_margin" => self.probe_margin = val.parse().expect("Got wrong val"),
+            _ => return false,
+        }
+        return true;
+    }
+
+    /// Whether the side to move may pass in `state`. Never twice in a row, never in a
+    /// PV node and never with few fishes left, where being forced to move often hurts
+    pub fn allows_null_move(
+        &self,
+        state: &GameState,
+        previous: Option<Move>,
+        depth: u8,
+        null_window: bool,
+    ) -> bool {
+        if !self.null_move || !null_window || previous.is_none() {
+            return false;
+        }
+        if depth <= self.null_move_reduction || state.turn >= NULL_MOVE_LAST_TURN {
+            return false;
+        }
+        let fishes = state
+            .board
+            .get_fields_of(&state.get_current_player_color())
+            .count_ones();
+        return fishes >= self.null_move_min_fishes as u32;
+    }
+
+    /// Depth a move is searched with, `index` being its position in the ordered move list
+    pub fn reduced_depth(&self, depth: u8, index: usize, capture: bool) -> u8 {
+        if !self.lmr || capture || depth < self.lmr_min_depth || index < self.lmr_full_moves {
+            return depth - 1;
+        }
+        return (depth - 1).saturating_sub(self.lmr_reduction);
+    }
+
+    /// Whether a quiet move can be skipped because even with the margin the static
+    /// evaluation stays below alpha
+    pub fn is_futile(&self, static_eval: f32, alpha: f32, depth: u8, capture: bool) -> bool {
+        if !self.futility || capture || depth > self.futility_max_depth {
+            return false;
+        }
+        return static_eval + self.futility_margin * depth as f32 <= alpha;
+    }
+}
+
+/// `state` with the side to move passing
+pub fn null_move(state: &GameState) -> GameState {
+    let mut state = state.clone();
+    state.turn += 1;
+    return state;
+}
+
+pub fn is_capture(state: &GameState, action: &Move) -> bool {
+    let dest_bit = 1u128 << (action.dest_x + action.dest_y * 10);
+    let enemy = state
+        .board
+        .get_fields_of(&state.get_current_player_color().get_opponent_color());
+    return enemy.bits & dest_bit != 0;
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::gamerules;
+
+    #[test]
+    fn reductions_only_for_late_quiet_moves() {
+        let mut params = DEFAULT_PRUNING;
+        params.lmr = true;
+        assert_eq!(params.reduced_depth(5, 0, false), 4);
+        assert_eq!(params.reduced_depth(5, 10, true), 4);
+        assert_eq!(params.reduced_depth(2, 10, false), 1);
+        assert_eq!(params.reduced_depth(5, 10, false), 3);
+    }
+
+    #[test]
+    fn no_double_null_move() {
+        let mut params = DEFAULT_PRUNING;
+        params.null_move = true;
+        params.null_move_min_fishes = 0;
+        let mut state = gamerules::get_seeded_state(1);
+        state.turn = 10;
+        let action = state.get_move_list()[0];
+        assert!(params.allows_null_move(&state, Some(action), 5, true));
+        assert!(!params.allows_null_move(&state, None, 5, true));
+        assert!(!params.allows_null_move(&state, Some(action), 5, false));
+        assert!(null_move(&state).get_current_player_color() != state.get_current_player_color());
+    }
+}
@@ -0,0 +1,122 @@
+use game_sdk::gamerules;
+use game_sdk::GameState;
+
+#[derive(Clone, Copy, Debug)]
+pub struct QuiescenceParameters {
+    /// most a single capture is expected to gain, positions further below alpha are not searched
+    pub delta_margin: f32,
+    /// plies of captures searched at most after the horizon
+    pub max_depth: u8,
+}
+
+pub const DEFAULT_QUIESCENCE: QuiescenceParameters = QuiescenceParameters {
+    delta_margin: 38.641,
+    max_depth: 8,
+};
+
+/// Capture-only search run at the horizon of the alpha-beta players.
+///
+/// `eval` rates quiet positions and `rate_end` finished games, both from red's point of view,
+/// so every engine keeps its own evaluation and mate scores.
+pub struct Quiescence<'a, E, T>
+where
+    E: Fn(&GameState) -> f32,
+    T: Fn(&GameState) -> f32,
+{
+    params: &'a QuiescenceParameters,
+    eval: E,
+    rate_end: T,
+    pub nodes: usize,
+}
+
+impl<'a, E, T> Quiescence<'a, E, T>
+where
+    E: Fn(&GameState) -> f32,
+    T: Fn(&GameState) -> f32,
+{
+    pub fn new(params: &'a QuiescenceParameters, eval: E, rate_end: T) -> Quiescence<'a, E, T> {
+        return Quiescence {
+            params,
+            eval,
+            rate_end,
+            nodes: 0,
+        };
+    }
+
+    pub fn search(&mut self, state: &GameState, alpha: f32, beta: f32, player_index: i8) -> f32 {
+        return self.search_ply(state, alpha, beta, player_index, 0);
+    }
+
+    fn search_ply(
+        &mut self,
+        state: &GameState,
+        mut alpha: f32,
+        beta: f32,
+        player_index: i8,
+        ply: u8,
+    ) -> f32 {
+        self.nodes += 1;
+        let standing_pat = player_index as f32 * (self.eval)(state);
+        if standing_pat >= beta || ply >= self.params.max_depth {
+            return standing_pat;
+        }
+        if standing_pat + self.params.delta_margin < alpha {
+            // delta pruning, not even a good capture would bring us back to alpha
+            return standing_pat;
+        }
+        alpha = f32::max(alpha, standing_pat);
+        let mut best = standing_pat;
+        let color = state.get_current_player_color();
+        for action in state.get_captures() {
+            let mut state = state.clone();
+            state.perform(&action, &color);
+            let rate = if gamerules::is_finished(&state) {
+                player_index as f32 * (self.rate_end)(&state)
+            } else {
+                -self.search_ply(&state, -beta, -alpha, -player_index, ply + 1)
+            };
+            if rate > best {
+                best = rate;
+                alpha = f32::max(alpha, rate);
+                if alpha >= beta {
+                    break;
+                }
+            }
+        }
+        return best;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::PlayerColor;
+
+    fn material(state: &GameState) -> f32 {
+        return state.board.get_fields_of(&PlayerColor::Red).count_ones() as f32
+            - state.board.get_fields_of(&PlayerColor::Blue).count_ones() as f32;
+    }
+
+    #[test]
+    fn stand_pat_cutoff() {
+        let state = gamerules::get_seeded_state(1);
+        let mut q = Quiescence::new(&DEFAULT_QUIESCENCE, material, material);
+        let rate = q.search(&state, -100., -50., 1);
+        assert_eq!(rate, material(&state));
+        assert_eq!(q.nodes, 1);
+    }
+
+    #[test]
+    fn never_below_stand_pat() {
+        for seed in 0..20 {
+            let state = gamerules::get_seeded_state(seed);
+            let player_index = match state.get_current_player_color() {
+                PlayerColor::Red => 1,
+                PlayerColor::Blue => -1,
+            };
+            let mut q = Quiescence::new(&DEFAULT_QUIESCENCE, material, material);
+            let rate = q.search(&state, -100., 100., player_index);
+            assert!(rate >= player_index as f32 * material(&state));
+        }
+    }
+}
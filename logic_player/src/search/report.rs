@@ -0,0 +1,44 @@
+use game_sdk::logging::{RootMove, MAX_PV_LENGTH};
+use game_sdk::{GameState, Move};
+
+use std::cmp::Ordering;
+
+use super::transposition::TranspositionTable;
+
+/// `part / whole`, 0 if there is no whole, so no statistic of a report ends up NaN or infinite
+pub fn ratio(part: f32, whole: f32) -> f32 {
+    if whole <= 0. {
+        return 0.;
+    }
+    return part / whole;
+}
+
+/// `action` followed by the best moves `tt` holds for the position after it
+pub fn line_after(tt: &TranspositionTable, state: &GameState, action: Move) -> Vec<Move> {
+    let mut state = state.clone();
+    let color = state.get_current_player_color();
+    state.perform(&action, &color);
+    let mut pv = vec![action];
+    pv.extend(tt.principal_variation(&state, MAX_PV_LENGTH - 1));
+    return pv;
+}
+
+/// The root moves of an alpha-beta search with the scores of the last iteration, the best
+/// first. Moves that failed low against the null window only have an upper bound as score
+pub fn rated_root_moves(
+    tt: &TranspositionTable,
+    state: &GameState,
+    rated_moves: &[(Move, f32)],
+) -> Vec<RootMove> {
+    let mut moves: Vec<RootMove> = rated_moves
+        .iter()
+        .map(|&(action, score)| RootMove {
+            action,
+            visits: None,
+            score,
+            pv: line_after(tt, state, action),
+        })
+        .collect();
+    moves.sort_by(|a, b| b.score.partial_cmp(&a.score).unwrap_or(Ordering::Equal));
+    return moves;
+}
@@ -0,0 +1,315 @@
+use game_sdk::gamerules;
+use game_sdk::{GameState, Move, PlayerColor};
+
+use time;
+
+const INFINITY: u32 = u32::MAX;
+
+#[derive(Clone, Copy, Debug)]
+pub struct SolverParameters {
+    /// positions from this turn on are solved
+    pub min_turn: u8,
+    /// positions where either color has at most this many fishes left are solved
+    pub max_fishes: u32,
+    /// nodes a single proof may create before the position counts as unsolved
+    pub max_nodes: usize,
+    pub max_millis: i64,
+}
+
+pub const DEFAULT_SOLVER: SolverParameters = SolverParameters {
+    min_turn: 55,
+    max_fishes: 4,
+    max_nodes: 500_000,
+    max_millis: 800,
+};
+
+/// Exact result of a position for the side to move, wins and losses with the number of plies
+/// until the game ends
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize)]
+pub enum Outcome {
+    Win(u8),
+    Draw,
+    Loss(u8),
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Solution {
+    pub outcome: Outcome,
+    /// the move reaching the outcome, the one resisting longest if the position is lost.
+    /// `None` if the game is already over or there is no move
+    pub action: Option<Move>,
+}
+
+struct Node {
+    state: GameState,
+    action: Option<Move>,
+    parent: Option<usize>,
+    children: Vec<usize>,
+    proof: u32,
+    disproof: u32,
+    /// plies until the game ends in the proof or disproof of this node
+    distance: u8,
+}
+
+impl Node {
+    fn is_solved(&self) -> bool {
+        return self.proof == 0 || self.disproof == 0;
+    }
+}
+
+/// Proof-number search for the end of the game.
+///
+/// A single search proves or disproves that `attacker` wins. `solve` combines two of them:
+/// whether the side to move wins and, if not, whether its opponent does. The position is a
+/// draw if neither holds. Mate distances are those of the proof found, the shortest win is
+/// not guaranteed.
+#[derive(Clone)]
+pub struct Solver {
+    params: SolverParameters,
+    /// nodes created by the last call to `solve`
+    pub nodes: usize,
+}
+
+impl Solver {
+    pub fn new(params: SolverParameters) -> Solver {
+        return Solver { params, nodes: 0 };
+    }
+
+    /// Whether `state` is close enough to the end of the game to be worth solving
+    pub fn applies(&self, state: &GameState) -> bool {
+        let fishes = u32::min(
+            state.board.red_fields.count_ones(),
+            state.board.blue_fields.count_ones(),
+        );
+        return state.turn >= self.params.min_turn || fishes <= self.params.max_fishes;
+    }
+
+    /// Solves `state`, `None` if the node or time budget ran out first
+    pub fn solve(&mut self, state: &GameState) -> Option<Solution> {
+        self.nodes = 0;
+        let start_time = time::now();
+        let color = state.get_current_player_color();
+
+        let tree = self.prove(state, color, &start_time)?;
+        let root = &tree[0];
+        if root.proof == 0 {
+            let best = root
+                .children
+                .iter()
+                .filter(|&&child| tree[child].proof == 0)
+                .min_by_key(|&&child| tree[child].distance);
+            return Some(Solution {
+                outcome: Outcome::Win(root.distance),
+                action: best.and_then(|&child| tree[child].action),
+            });
+        }
+
+        let tree = self.prove(state, color.get_opponent_color(), &start_time)?;
+        let root = &tree[0];
+        if root.proof == 0 {
+            let longest = root
+                .children
+                .iter()
+                .max_by_key(|&&child| tree[child].distance);
+            return Some(Solution {
+                outcome: Outcome::Loss(root.distance),
+                action: longest.and_then(|&child| tree[child].action),
+            });
+        }
+        let holding = root
+            .children
+            .iter()
+            .find(|&&child| tree[child].disproof == 0);
+        return Some(Solution {
+            outcome: Outcome::Draw,
+            action: holding.and_then(|&child| tree[child].action),
+        });
+    }
+
+    /// Tree of a finished search whether `attacker` wins `state`, `None` if it ran out of budget
+    fn prove(
+        &mut self,
+        state: &GameState,
+        attacker: PlayerColor,
+        start_time: &time::Tm,
+    ) -> Option<Vec<Node>> {
+        let mut tree = vec![leaf(state.clone(), None, None, attacker)];
+        self.nodes += 1;
+        while !tree[0].is_solved() {
+            if self.nodes > self.params.max_nodes
+                || (time::now() - *start_time).num_milliseconds() > self.params.max_millis
+            {
+                return None;
+            }
+            let mut current = most_proving(&tree, attacker);
+            self.expand(&mut tree, current, attacker);
+            loop {
+                update(&mut tree, current, attacker);
+                match tree[current].parent {
+                    Some(parent) => current = parent,
+                    None => break,
+                }
+            }
+        }
+        return Some(tree);
+    }
+
+    fn expand(&mut self, tree: &mut Vec<Node>, index: usize, attacker: PlayerColor) {
+        let color = tree[index].state.get_current_player_color();
+        let moves = tree[index].state.get_move_list();
+        if moves.is_empty() {
+            // like the alpha-beta players, a color without moves has lost
+            let node = &mut tree[index];
+            if color == attacker {
+                node.proof = INFINITY;
+                node.disproof = 0;
+            } else {
+                node.proof = 0;
+                node.disproof = INFINITY;
+            }
+            return;
+        }
+        for action in moves {
+            let mut state = tree[index].state.clone();
+            state.perform(&action, &color);
+            let child = tree.len();
+            tree.push(leaf(state, Some(action), Some(index), attacker));
+            tree[index].children.push(child);
+            self.nodes += 1;
+        }
+    }
+}
+
+fn leaf(
+    state: GameState,
+    action: Option<Move>,
+    parent: Option<usize>,
+    attacker: PlayerColor,
+) -> Node {
+    let (proof, disproof) = if gamerules::is_finished(&state) {
+        if gamerules::get_winner(&state) == Some(attacker) {
+            (0, INFINITY)
+        } else {
+            (INFINITY, 0)
+        }
+    } else {
+        (1, 1)
+    };
+    return Node {
+        state,
+        action,
+        parent,
+        children: Vec::new(),
+        proof,
+        disproof,
+        distance: 0,
+    };
+}
+
+/// Follows the children that decide the proof numbers down to an unexpanded node
+fn most_proving(tree: &[Node], attacker: PlayerColor) -> usize {
+    let mut index = 0;
+    while !tree[index].children.is_empty() {
+        let node = &tree[index];
+        let or_node = node.state.get_current_player_color() == attacker;
+        index = *node
+            .children
+            .iter()
+            .find(|&&child| {
+                if or_node {
+                    tree[child].proof == node.proof
+                } else {
+                    tree[child].disproof == node.disproof
+                }
+            })
+            .expect("No child matches the proof numbers");
+    }
+    return index;
+}
+
+fn update(tree: &mut [Node], index: usize, attacker: PlayerColor) {
+    if tree[index].children.is_empty() {
+        return;
+    }
+    let or_node = tree[index].state.get_current_player_color() == attacker;
+    let mut min_proof = INFINITY;
+    let mut min_disproof = INFINITY;
+    let mut sum_proof: u32 = 0;
+    let mut sum_disproof: u32 = 0;
+    for &child in tree[index].children.iter() {
+        min_proof = u32::min(min_proof, tree[child].proof);
+        min_disproof = u32::min(min_disproof, tree[child].disproof);
+        sum_proof = sum_proof.saturating_add(tree[child].proof);
+        sum_disproof = sum_disproof.saturating_add(tree[child].disproof);
+    }
+    let (proof, disproof) = if or_node {
+        (min_proof, sum_disproof)
+    } else {
+        (sum_proof, min_disproof)
+    };
+
+    // the side choosing takes the shortest way to a result it wants, the other one the longest
+    let distances = tree[index].children.iter().map(|&child| &tree[child]);
+    let distance = if proof == 0 && or_node {
+        distances
+            .filter(|child| child.proof == 0)
+            .map(|child| child.distance)
+            .min()
+    } else if disproof == 0 && !or_node {
+        distances
+            .filter(|child| child.disproof == 0)
+            .map(|child| child.distance)
+            .min()
+    } else if proof == 0 || disproof == 0 {
+        distances.map(|child| child.distance).max()
+    } else {
+        None
+    };
+
+    let node = &mut tree[index];
+    node.proof = proof;
+    node.disproof = disproof;
+    node.distance = distance.map_or(0, |distance| distance + 1);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::{Board, FieldType};
+
+    fn state_with(red: &[(usize, usize)], blue: &[(usize, usize)], turn: u8) -> GameState {
+        let mut fields = [[FieldType::Free; 10]; 10];
+        for &(x, y) in red {
+            fields[x][y] = FieldType::RedPlayer;
+        }
+        for &(x, y) in blue {
+            fields[x][y] = FieldType::BluePlayer;
+        }
+        return GameState::new(Board::new(fields), turn);
+    }
+
+    #[test]
+    fn connecting_wins() {
+        // red joins its two fishes in one move, blue can neither connect nor capture
+        let state = state_with(&[(0, 0), (2, 1)], &[(9, 9), (9, 5)], 58);
+        let mut solver = Solver::new(DEFAULT_SOLVER);
+        assert!(solver.applies(&state));
+        let solution = solver.solve(&state).expect("Could not solve");
+        assert_eq!(solution.outcome, Outcome::Win(2));
+        let action = solution.action.expect("No winning move");
+        let mut state = state;
+        state.perform(&action, &PlayerColor::Red);
+        assert!(state.is_connected(&PlayerColor::Red));
+        let solution = solver.solve(&state).expect("Could not solve");
+        assert_eq!(solution.outcome, Outcome::Loss(1));
+    }
+
+    #[test]
+    fn equal_swarms_draw() {
+        let state = state_with(&[(0, 0), (5, 5)], &[(9, 9), (9, 5)], 59);
+        let mut solver = Solver::new(DEFAULT_SOLVER);
+        let solution = solver.solve(&state).expect("Could not solve");
+        assert_eq!(solution.outcome, Outcome::Draw);
+        assert!(solution.action.is_some());
+    }
+}
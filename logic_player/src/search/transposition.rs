@@ -0,0 +1,344 @@
+use game_sdk::gamerules;
+use game_sdk::GameState;
+use game_sdk::Move;
+
+use std::mem;
+
+use super::report::ratio;
+
+/// Size every alpha-beta player allocates unless told otherwise
+pub const DEFAULT_SIZE_MB: usize = 16;
+/// Number of entries sharing one index of the table
+pub const BUCKET_SIZE: usize = 4;
+/// Generations an entry may lag behind before it counts as stale
+const AGE_WEIGHT: i32 = 8;
+
+const BYTES_PER_MB: usize = 1024 * 1024;
+
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum EntryType {
+    Exact,
+    UpperBound,
+    LowerBound,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct HashEntry {
+    pub value: f32,
+    pub action: Move,
+    pub depth: u8,
+    pub entry: EntryType,
+}
+
+#[derive(Eq, PartialEq, Hash, Clone, Copy, Debug)]
+pub struct MinimalState {
+    red_fields: u128,
+    blue_fields: u128,
+    turn: u8,
+}
+
+impl MinimalState {
+    pub fn from_state(state: &GameState) -> MinimalState {
+        return MinimalState {
+            red_fields: state.board.red_fields.bits,
+            blue_fields: state.board.blue_fields.bits,
+            turn: state.turn,
+        };
+    }
+
+    #[allow(unused)]
+    pub fn empty() -> MinimalState {
+        return MinimalState {
+            red_fields: 0,
+            blue_fields: 0,
+            turn: 255,
+        };
+    }
+
+    /// 64-bit key used to index the table and to verify entries
+    pub fn key(&self) -> u64 {
+        let mut key = mix(self.turn as u64);
+        key = mix(key ^ (self.red_fields as u64));
+        key = mix(key ^ ((self.red_fields >> 64) as u64));
+        key = mix(key ^ (self.blue_fields as u64));
+        key = mix(key ^ ((self.blue_fields >> 64) as u64));
+        return key;
+    }
+}
+
+/// splitmix64 finalizer, spreads the few set bits of a board over the whole key
+pub fn mix(mut x: u64) -> u64 {
+    x ^= x >> 30;
+    x = x.wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    x ^= x >> 27;
+    x = x.wrapping_mul(0x94d0_49bb_1331_11eb);
+    return x ^ (x >> 31);
+}
+
+#[derive(Clone, Copy)]
+struct Slot {
+    key: u64,
+    data: HashEntry,
+    generation: u8,
+}
+
+type Bucket = [Option<Slot>; BUCKET_SIZE];
+
+/// Counters since the last call to `new_search`
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TableStatistics {
+    pub probes: usize,
+    pub hits: usize,
+    /// probes that found their bucket filled by other positions only
+    pub collisions: usize,
+    pub stores: usize,
+    /// stores that evicted an entry of another position
+    pub replacements: usize,
+}
+
+impl TableStatistics {
+    pub fn hit_rate(&self) -> f32 {
+        return ratio(self.hits as f32, self.probes as f32);
+    }
+
+    pub fn collision_rate(&self) -> f32 {
+        return ratio(self.collisions as f32, self.probes as f32);
+    }
+}
+
+/// Fixed-size transposition table.
+///
+/// Positions are hashed into buckets of `BUCKET_SIZE` entries. Within a bucket an entry of
+/// the same position is overwritten by deeper or exact results, otherwise the shallowest
+/// entry is evicted, where every search generation an entry is old counts as `AGE_WEIGHT`
+/// plies of depth lost. The memory is only allocated on first use, so cloning a player
+/// copies the configured size but not the content of the table.
+pub struct TranspositionTable {
+    buckets: Vec<Bucket>,
+    bucket_count: usize,
+    generation: u8,
+    pub stats: TableStatistics,
+}
+
+impl TranspositionTable {
+    pub fn new(size_mb: usize) -> TranspositionTable {
+        let wanted = usize::max(1, size_mb * BYTES_PER_MB / mem::size_of::<Bucket>());
+        // round down to a power of two, so indexing is a simple mask
+        let mut bucket_count = 1;
+        while bucket_count * 2 <= wanted {
+            bucket_count *= 2;
+        }
+        return TranspositionTable {
+            buckets: Vec::new(),
+            bucket_count,
+            generation: 0,
+            stats: TableStatistics::default(),
+        };
+    }
+
+    /// Must be called once before every search from a new root. Entries of earlier
+    /// searches stay usable but become the first candidates for replacement
+    pub fn new_search(&mut self) {
+        self.generation = self.generation.wrapping_add(1);
+        self.stats = TableStatistics::default();
+    }
+
+    /// Permille of the first 1000 buckets' entries that were written in the current search
+    pub fn hashfull(&self) -> usize {
+        let sampled = usize::min(1000, self.buckets.len());
+        let mut used = 0;
+        for bucket in self.buckets.iter().take(sampled) {
+            for slot in bucket.iter().flatten() {
+                if slot.generation == self.generation {
+                    used += 1;
+                }
+            }
+        }
+        if sampled == 0 {
+            return 0;
+        }
+        return used * 1000 / (sampled * BUCKET_SIZE);
+    }
+
+    fn index(&self, key: u64) -> usize {
+        return (key as usize) & (self.bucket_count - 1);
+    }
+
+    pub fn insert(
+        &mut self,
+        hash: &MinimalState,
+        value: f32,
+        depth: u8,
+        action: &Move,
+        entry: EntryType,
+    ) {
+        if self.buckets.is_empty() {
+            self.buckets = vec![[None; BUCKET_SIZE]; self.bucket_count];
+        }
+        self.stats.stores += 1;
+        let key = hash.key();
+        let index = self.index(key);
+        let generation = self.generation;
+        let bucket = &mut self.buckets[index];
+        let new_slot = Slot {
+            key,
+            data: HashEntry {
+                value,
+                depth,
+                action: *action,
+                entry,
+            },
+            generation,
+        };
+
+        let mut victim = 0;
+        let mut victim_score = i32::MAX;
+        for (i, slot) in bucket.iter_mut().enumerate() {
+            match slot {
+                Some(ref mut old) if old.key == key => {
+                    if entry == EntryType::Exact
+                        || depth >= old.data.depth
+                        || old.generation != generation
+                    {
+                        *old = new_slot;
+                    }
+                    return;
+                }
+                Some(old) => {
+                    let age = generation.wrapping_sub(old.generation) as i32;
+                    let score = old.data.depth as i32 - AGE_WEIGHT * age;
+                    if score < victim_score {
+                        victim_score = score;
+                        victim = i;
+                    }
+                }
+                None => {
+                    if victim_score > i32::MIN {
+                        victim_score = i32::MIN;
+                        victim = i;
+                    }
+                }
+            }
+        }
+        if bucket[victim].is_some() {
+            self.stats.replacements += 1;
+        }
+        bucket[victim] = Some(new_slot);
+    }
+
+    pub fn lookup(&mut self, hash: &MinimalState) -> Option<HashEntry> {
+        self.stats.probes += 1;
+        if self.buckets.is_empty() {
+            return None;
+        }
+        let key = hash.key();
+        let index = self.index(key);
+        let generation = self.generation;
+        let mut occupied = false;
+        for slot in self.buckets[index].iter_mut().flatten() {
+            if slot.key == key {
+                self.stats.hits += 1;
+                // still useful in this search, so protect it from replacement
+                slot.generation = generation;
+                return Some(slot.data);
+            }
+            occupied = true;
+        }
+        if occupied {
+            self.stats.collisions += 1;
+        }
+        return None;
+    }
+
+    /// The stored best moves from `state` on, at most `max_len` of them. Unlike `lookup`
+    /// this leaves the statistics and the age of the entries alone
+    pub fn principal_variation(&self, state: &GameState, max_len: usize) -> Vec<Move> {
+        let mut pv = Vec::new();
+        let mut state = state.clone();
+        while pv.len() < max_len && !self.buckets.is_empty() && !gamerules::is_finished(&state) {
+            let key = MinimalState::from_state(&state).key();
+            let entry = self.buckets[self.index(key)]
+                .iter()
+                .flatten()
+                .find(|slot| slot.key == key)
+                .map(|slot| slot.data);
+            let action = match entry {
+                Some(entry) if state.get_move_list().contains(&entry.action) => entry.action,
+                _ => break,
+            };
+            let color = state.get_current_player_color();
+            state.perform(&action, &color);
+            pv.push(action);
+        }
+        return pv;
+    }
+}
+
+impl Clone for TranspositionTable {
+    fn clone(&self) -> TranspositionTable {
+        return TranspositionTable {
+            buckets: Vec::new(),
+            bucket_count: self.bucket_count,
+            generation: 0,
+            stats: TableStatistics::default(),
+        };
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use game_sdk::{gamerules, Direction};
+
+    fn some_move() -> Move {
+        return Move::new(0, 1, 1, 1, Direction::Right);
+    }
+
+    #[test]
+    fn insert_and_lookup() {
+        let mut tt = TranspositionTable::new(1);
+        let state = MinimalState::from_state(&gamerules::get_seeded_state(1));
+        assert!(tt.lookup(&state).is_none());
+        tt.insert(&state, 1.5, 3, &some_move(), EntryType::Exact);
+        let entry = tt.lookup(&state).expect("Entry was just inserted");
+        assert_eq!(entry.value, 1.5);
+        assert_eq!(entry.depth, 3);
+        assert!(entry.entry == EntryType::Exact);
+        assert_eq!(tt.stats.hits, 1);
+    }
+
+    #[test]
+    fn depth_preferred() {
+        let mut tt = TranspositionTable::new(1);
+        let state = MinimalState::from_state(&gamerules::get_seeded_state(2));
+        tt.insert(&state, 1.0, 5, &some_move(), EntryType::LowerBound);
+        tt.insert(&state, 2.0, 2, &some_move(), EntryType::LowerBound);
+        assert_eq!(tt.lookup(&state).unwrap().depth, 5);
+        tt.new_search();
+        tt.insert(&state, 2.0, 2, &some_move(), EntryType::LowerBound);
+        assert_eq!(tt.lookup(&state).unwrap().depth, 2);
+    }
+
+    #[test]
+    fn bounded_size() {
+        let mut tt = TranspositionTable::new(1);
+        assert!(tt.bucket_count * mem::size_of::<Bucket>() <= BYTES_PER_MB);
+        let mut state = gamerules::get_seeded_state(3);
+        for turn in 0..60 {
+            for _ in 0..2000 {
+                state.board.red_fields.bits =
+                    state.board.red_fields.bits.wrapping_mul(3).wrapping_add(1);
+                state.turn = turn;
+                tt.insert(
+                    &MinimalState::from_state(&state),
+                    0.,
+                    1,
+                    &some_move(),
+                    EntryType::Exact,
+                );
+            }
+        }
+        assert!(tt.stats.replacements > 0);
+        assert_eq!(tt.clone().buckets.len(), 0);
+    }
+}
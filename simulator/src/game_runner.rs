@@ -1,6 +1,6 @@
 // random vs random: 529 380 / 122 399 it/s (multi/single) (avg over 1M iterations)
 // those speeds won't ever be reproducible, as they were patched out
-use game_sdk::{gamerules, ClientListener, GameState, PlayerColor};
+use game_sdk::{gamerules, ClientListener, GameState, Move, PlayerColor};
 
 use game_sdk::logging::{Data, EndState, Winner};
 use rand::{thread_rng, Rng};
@@ -9,9 +9,10 @@ use xml_utils;
 use logic_player::RavePlayer as PlayerOne;
 
 use logic_player::LegacyRavePlayer as PlayerTwo;
+use logic_player::{HybridPlayer, PlayoutPolicy, SwitchPolicy};
 
 use std::fs;
-use std::sync::mpsc;
+use std::sync::{mpsc, Arc};
 
 pub fn run_game_wo_sending(index: u32) {
     let mut player_one = PlayerOne::new(None, index as i64);
@@ -46,6 +47,51 @@ pub fn run_single_game(
 ) {
     let mut player_one = PlayerOne::new(None, index as i64);
     let mut player_two = PlayerTwo::new(None, index as i64);
+    play_game(index, t_winner, &mut player_one, &mut player_two, xml_enabled);
+}
+
+/// A hybrid switching engines by `policy` against one switching at the default turn
+pub fn run_policy_game(
+    index: u32,
+    t_winner: mpsc::Sender<(Winner, Option<PlayerColor>)>,
+    policy: SwitchPolicy,
+    xml_enabled: bool,
+) {
+    let mut player_one = HybridPlayer::with_policy(None, index as i64, policy);
+    let mut player_two = HybridPlayer::new(None, index as i64);
+    play_game(index, t_winner, &mut player_one, &mut player_two, xml_enabled);
+}
+
+/// A RAVE player playing out with `playout` against one with the greedy playout, the search
+/// speed of both is printed after the game
+pub fn run_playout_game(
+    index: u32,
+    t_winner: mpsc::Sender<(Winner, Option<PlayerColor>)>,
+    playout: Arc<dyn PlayoutPolicy>,
+    xml_enabled: bool,
+) {
+    let mut player_one = PlayerOne::new(None, index as i64);
+    player_one.set_playout(playout);
+    let mut player_two = PlayerOne::new(None, index as i64);
+    play_game(index, t_winner, &mut player_one, &mut player_two, xml_enabled);
+    println!(
+        "game {}: {:.0} it/s against {:.0} it/s greedy",
+        index,
+        player_one.iterations_per_s(),
+        player_two.iterations_per_s()
+    );
+}
+
+fn play_game<D, T>(
+    index: u32,
+    t_winner: mpsc::Sender<(Winner, Option<PlayerColor>)>,
+    player_one: &mut D,
+    player_two: &mut T,
+    xml_enabled: bool,
+) where
+    D: ClientListener,
+    T: ClientListener,
+{
     let mut string_xml = "<protocol>\n".to_string();
     let mut state = gamerules::get_random_state();
     loop {
@@ -166,6 +212,36 @@ pub fn collect_selfplay(
     send_winner(t_winner, &state, index);
 }
 
+/// A self-play game for the opening book: the start position, the moves and the winner
+pub type BookGame = (GameState, Vec<Move>, Option<PlayerColor>);
+
+pub fn collect_book(
+    index: u32,
+    t_book: mpsc::Sender<BookGame>,
+    t_winner: mpsc::Sender<(Winner, Option<PlayerColor>)>,
+) {
+    let mut player = logic_player::RavePlayer::new(None, index as i64);
+    let start = gamerules::get_random_state();
+    let mut state = start.clone();
+    let mut moves = Vec::new();
+    loop {
+        if state.turn % 2 == 0 {
+            if gamerules::is_finished(&state) {
+                break;
+            }
+        }
+
+        let action = player.on_move_request(&state);
+        let color = state.get_current_player_color();
+        state.perform(&action, &color);
+        moves.push(action);
+    }
+    t_book
+        .send((start, moves, gamerules::get_winner(&state)))
+        .unwrap();
+    send_winner(t_winner, &state, index);
+}
+
 pub fn collect_data(
     index: u32,
     t_log: mpsc::Sender<Data>,
@@ -11,16 +11,25 @@ mod tournament;
 mod xml_utils;
 
 use argparse::{ArgumentParser, Store, StoreTrue};
-use game_runner::{collect_data, collect_selfplay, run_game_wo_sending, run_single_game};
+use game_runner::{
+    collect_book, collect_data, collect_selfplay, run_game_wo_sending, run_playout_game,
+    run_policy_game, run_single_game, BookGame,
+};
 use game_sdk::logging::{Data, Winner};
 use game_sdk::PlayerColor;
 use logger::Logger;
+use logic_player::{playout_from_string, OpeningBook, SwitchPolicy};
 use tournament::run_tournament;
 
+use std::fs;
+use std::io;
 use std::sync::mpsc;
 use std::thread;
 use threadpool::ThreadPool;
 
+/// Where the logging modes append the search reports of the engines, one JSON object per line
+const REPORT_FILE: &str = "replays/reports.jsonl";
+
 fn main() {
     let mut n = 1;
     let mut xml_enabled = false;
@@ -29,6 +38,10 @@ fn main() {
     let mut benchmarking = false;
     let mut tournament = false;
     let mut selfplay = false;
+    let mut book_path = String::new();
+    let mut book_turns: u8 = 10;
+    let mut policy = String::new();
+    let mut playout = String::new();
     {
         let mut ap = ArgumentParser::new();
         ap.refer(&mut n)
@@ -60,11 +73,31 @@ fn main() {
             StoreTrue,
             "If set, logged self-play is performed",
         );
+        ap.refer(&mut book_path).add_option(
+            &["-o", "--book"],
+            Store,
+            "Opening book to extend with self-play games, generation mode if set",
+        );
+        ap.refer(&mut book_turns).add_option(
+            &["--book-turns"],
+            Store,
+            "Turns of every game added to the opening book, defaults to 10",
+        );
+        ap.refer(&mut policy).add_option(
+            &["-p", "--policy"],
+            Store,
+            "Switch policy of a hybrid playing the default hybrid, e.g. turn=20 or confidence=0.6",
+        );
+        ap.refer(&mut playout).add_option(
+            &["--playout"],
+            Store,
+            "Playout of a RAVE player playing one with greedy playouts, e.g. random, epsilon=0.1, softmax=50 or cutoff=8",
+        );
         ap.parse_args_or_exit();
     }
 
-    println!("Starting with parameters:\nn:{}\nxml:{}\ncollection:{}\nthreads:{}\nbenchmark:{}\ntourney:{}\nrl:{}\n",
-                n, xml_enabled, data_collection, threads, benchmarking, tournament, selfplay);
+    println!("Starting with parameters:\nn:{}\nxml:{}\ncollection:{}\nthreads:{}\nbenchmark:{}\ntourney:{}\nrl:{}\nbook:{}\n",
+                n, xml_enabled, data_collection, threads, benchmarking, tournament, selfplay, book_path);
 
     if tournament {
         run_tournament(threads, n, xml_enabled);
@@ -81,37 +114,80 @@ fn main() {
     let mut handle = None;
     if data_collection {
         let mut logger = Logger::new("replays/vals/".to_string());
+        logger.set_report_file(REPORT_FILE);
         handle = Some(thread::spawn(move || {
             for received in r_log {
                 match received {
                     Data::Step(state) => logger.add_state(&state),
                     Data::End(state) => logger.end_state(&state),
+                    Data::Report(report) => logger.add_report(&report),
                 }
             }
         }));
     } else if selfplay {
         let mut logger = Logger::new("replays/vals/".to_string());
+        logger.set_report_file(REPORT_FILE);
         handle = Some(thread::spawn(move || {
             for received in r_log {
                 match received {
                     Data::Step(state) => logger.add_state(&state),
                     Data::End(state) => logger.end_state(&state),
+                    Data::Report(report) => logger.add_report(&report),
                 }
             }
         }));
     }
 
+    let (t_book, r_book): (mpsc::Sender<BookGame>, mpsc::Receiver<BookGame>) = mpsc::channel();
+    let book_generation = !book_path.is_empty();
+    let mut book_handle = None;
+    if book_generation {
+        // a missing book is started from scratch, anything else would be overwritten
+        let mut book = match fs::read_to_string(&book_path) {
+            Ok(content) => OpeningBook::parse(&content)
+                .unwrap_or_else(|e| panic!("Invalid opening book {}: {}", book_path, e)),
+            Err(ref e) if e.kind() == io::ErrorKind::NotFound => OpeningBook::new(),
+            Err(e) => panic!("Unable to read opening book {}: {}", book_path, e),
+        };
+        let book_path = book_path.clone();
+        book_handle = Some(thread::spawn(move || {
+            for (start, moves, winner) in r_book {
+                book.add_game(&start, &moves, winner, book_turns);
+            }
+            fs::write(&book_path, book.to_text()).expect("Unable to write opening book");
+            println!("Wrote {} positions to {}", book.len(), book_path);
+        }));
+    }
+
+    let policy = if policy.is_empty() {
+        None
+    } else {
+        Some(SwitchPolicy::from_string(&policy).expect("Invalid switch policy"))
+    };
+
+    let playout = if playout.is_empty() {
+        None
+    } else {
+        Some(playout_from_string(&playout).expect("Invalid playout"))
+    };
+
     let pool = ThreadPool::new(threads);
 
     for i in 0..n {
         // different modes to run in:
         // - selfplay
         // - data_collection
+        // - book generation
         // - normal
 
         let t_winner = t_winner.clone();
         let index = i;
-        if data_collection {
+        if book_generation {
+            let t_book = t_book.clone();
+            pool.execute(move || {
+                collect_book(index as u32, t_book, t_winner);
+            });
+        } else if data_collection {
             let t_log = t_log.clone();
             pool.execute(move || {
                 collect_data(index as u32, t_log, t_winner);
@@ -121,6 +197,15 @@ fn main() {
             pool.execute(move || {
                 collect_selfplay(index as u32, t_log, t_winner);
             });
+        } else if let Some(policy) = policy {
+            pool.execute(move || {
+                run_policy_game(index as u32, t_winner, policy, xml_enabled);
+            });
+        } else if let Some(ref playout) = playout {
+            let playout = playout.clone();
+            pool.execute(move || {
+                run_playout_game(index as u32, t_winner, playout, xml_enabled);
+            });
         } else if !benchmarking {
             pool.execute(move || {
                 run_single_game(index as u32, t_winner, xml_enabled);
@@ -217,10 +302,12 @@ fn main() {
         (n as f32 / millis_taken as f32) * 1000.
     );
     drop(t_log);
-    if data_collection {
-        handle
-            .expect("Should have been initialized, ERROR")
-            .join()
-            .unwrap();
+    drop(t_book);
+    if let Some(handle) = book_handle {
+        handle.join().unwrap();
+    }
+    // the logger flushes the buffered reports once the channel is closed
+    if let Some(handle) = handle {
+        handle.join().unwrap();
     }
 }
@@ -0,0 +1,92 @@
+use std::fs;
+
+#[derive(Clone, Debug)]
+pub struct Parameter {
+    pub name: String,
+    pub min: f32,
+    pub max: f32,
+    pub integer: bool,
+}
+
+impl Parameter {
+    /// Value of the parameter at `x`, 0 being `min` and 1 being `max`
+    pub fn value(&self, x: f32) -> f32 {
+        let value = self.unrounded(x);
+        if self.integer {
+            return value.round();
+        }
+        return value;
+    }
+
+    /// Value at `x` before integer parameters are rounded, what checkpoints store
+    pub fn unrounded(&self, x: f32) -> f32 {
+        return self.min + x * (self.max - self.min);
+    }
+
+    /// Inverse of `unrounded`
+    pub fn normalize(&self, value: f32) -> f32 {
+        return (value - self.min) / (self.max - self.min);
+    }
+
+    /// `value` the way `set_var_from_string` expects it
+    pub fn format(&self, x: f32) -> String {
+        if self.integer {
+            return format!("{}", self.value(x) as i64);
+        }
+        return format!("{}", self.value(x));
+    }
+}
+
+/// The parameters to tune, read from a CLOP experiment file. Only the parameter and
+/// `Processor` lines are used, everything else only concerned the external tool
+pub struct Experiment {
+    pub parameters: Vec<Parameter>,
+    pub processors: usize,
+}
+
+impl Experiment {
+    pub fn from_file(path: &str) -> Experiment {
+        let content = fs::read_to_string(path).expect("Unable to read experiment file");
+        let mut parameters = Vec::new();
+        let mut processors = 0;
+        for line in content.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.is_empty() || words[0].starts_with('#') {
+                continue;
+            }
+            match words[0] {
+                "LinearParameter" | "GammaParameter" => {
+                    parameters.push(Experiment::parse_parameter(&words, false))
+                }
+                "IntegerParameter" | "IntegerGammaParameter" => {
+                    parameters.push(Experiment::parse_parameter(&words, true))
+                }
+                "Processor" => processors += 1,
+                _ => {}
+            }
+        }
+        if parameters.is_empty() {
+            panic!("Experiment file does not define any parameters");
+        }
+        return Experiment {
+            parameters,
+            processors,
+        };
+    }
+
+    fn parse_parameter(words: &[&str], integer: bool) -> Parameter {
+        if words.len() != 4 {
+            panic!("Parameter lines must be <type> <name> <min> <max>");
+        }
+        let parameter = Parameter {
+            name: words[1].to_string(),
+            min: words[2].parse().expect("Got wrong min"),
+            max: words[3].parse().expect("Got wrong max"),
+            integer,
+        };
+        if parameter.max <= parameter.min {
+            panic!("Parameter {} has an empty range", parameter.name);
+        }
+        return parameter;
+    }
+}
@@ -0,0 +1,159 @@
+mod experiment;
+mod spsa;
+
+use argparse::{ArgumentParser, Store, StoreTrue};
+use game_sdk::{gamerules, ClientListener, PlayerColor};
+use logic_player::{ClopParameters, ToClop};
+use rand::{thread_rng, Rng};
+use std::sync::mpsc;
+use threadpool::ThreadPool;
+
+use crate::experiment::Experiment;
+use crate::spsa::{Spsa, SpsaSettings};
+
+fn new_player(assignments: &[(String, String)]) -> ToClop {
+    let mut params = ClopParameters::empty();
+    for (identifier, val) in assignments {
+        params.set_var_from_string(identifier.clone(), val.clone());
+    }
+    return ToClop::new(params);
+}
+
+/// Plays one game and returns the score of the `plus` player, 1 for a win, 0.5 for a draw
+fn play_game(plus: &[(String, String)], minus: &[(String, String)], plus_is_red: bool) -> f32 {
+    let mut state = gamerules::get_random_state();
+    let mut red = new_player(if plus_is_red { plus } else { minus });
+    let mut blue = new_player(if plus_is_red { minus } else { plus });
+    let plus_color = if plus_is_red {
+        PlayerColor::Red
+    } else {
+        PlayerColor::Blue
+    };
+
+    loop {
+        if state.turn % 2 == 0 && gamerules::is_finished(&state) {
+            break;
+        }
+        let color = state.get_current_player_color();
+        if state.get_move_list().is_empty() {
+            // the side to move is stuck and loses
+            return if color == plus_color { 0. } else { 1. };
+        }
+        let action = match color {
+            PlayerColor::Red => red.on_move_request(&state),
+            PlayerColor::Blue => blue.on_move_request(&state),
+        };
+        state.perform(&action, &color);
+    }
+
+    return match gamerules::get_winner(&state) {
+        Some(color) if color == plus_color => 1.,
+        Some(_) => 0.,
+        None => 0.5,
+    };
+}
+
+/// Plays `games` games between the two perturbations with alternating colors and returns
+/// the score of `plus` from -1 to 1
+fn play_batch(
+    pool: &ThreadPool,
+    plus: Vec<(String, String)>,
+    minus: Vec<(String, String)>,
+    games: usize,
+) -> f32 {
+    let (tx, rx) = mpsc::channel();
+    for index in 0..games {
+        let tx = tx.clone();
+        let plus = plus.clone();
+        let minus = minus.clone();
+        pool.execute(move || {
+            tx.send(play_game(&plus, &minus, index % 2 == 0))
+                .expect("Unable to send result");
+        });
+    }
+    let score: f32 = rx.iter().take(games).sum();
+    return 2. * score / games as f32 - 1.;
+}
+
+fn print_parameters(spsa: &Spsa) {
+    for (identifier, val) in spsa.assignments(&spsa.theta) {
+        println!("  {} {}", identifier, val);
+    }
+}
+
+fn main() {
+    let mut file = "spsa_tuner/rave.clop".to_string();
+    let mut checkpoint = "spsa.checkpoint".to_string();
+    let mut resume = false;
+    let mut iterations = 1000;
+    let mut games = 8;
+    let mut threads = 0;
+    let mut settings = SpsaSettings {
+        a: 5e-3,
+        c: 0.05,
+        big_a: 0.,
+    };
+    {
+        let mut ap = ArgumentParser::new();
+        ap.refer(&mut file).add_option(
+            &["-f", "--file"],
+            Store,
+            "CLOP experiment file defining the parameters",
+        );
+        ap.refer(&mut checkpoint).add_option(
+            &["-c", "--checkpoint"],
+            Store,
+            "File the progress is saved to after every iteration",
+        );
+        ap.refer(&mut resume).add_option(
+            &["-r", "--resume"],
+            StoreTrue,
+            "Continue from the checkpoint instead of starting over",
+        );
+        ap.refer(&mut iterations)
+            .add_option(&["-i", "--iterations"], Store, "Iterations to run");
+        ap.refer(&mut games).add_option(
+            &["-g", "--games"],
+            Store,
+            "Games per iteration, rounded up to an even number",
+        );
+        ap.refer(&mut threads).add_option(
+            &["-t", "--threads"],
+            Store,
+            "Games played in parallel, defaults to the processors of the experiment",
+        );
+        ap.refer(&mut settings.a)
+            .add_option(&["--step"], Store, "Update size a");
+        ap.refer(&mut settings.c)
+            .add_option(&["--perturbation"], Store, "Perturbation size c");
+        ap.parse_args_or_exit();
+    }
+    games += games % 2;
+    settings.big_a = iterations as f32 / 10.;
+
+    let experiment = Experiment::from_file(&file);
+    if threads == 0 {
+        threads = usize::max(1, experiment.processors);
+    }
+    let mut spsa = Spsa::new(experiment.parameters, settings);
+    if resume && spsa.resume(&checkpoint) {
+        println!("Resuming at iteration {}", spsa.iteration);
+    }
+    let pool = ThreadPool::new(threads);
+
+    while spsa.iteration < iterations {
+        let mut rng = thread_rng();
+        let delta: Vec<f32> = spsa
+            .theta
+            .iter()
+            .map(|_| if rng.gen::<bool>() { 1. } else { -1. })
+            .collect();
+        let plus = spsa.assignments(&spsa.perturbed(&delta, 1.));
+        let minus = spsa.assignments(&spsa.perturbed(&delta, -1.));
+        let score = play_batch(&pool, plus, minus, games);
+        spsa.update(&delta, score);
+        spsa.save(&checkpoint);
+        println!("iteration {} | score {:.3}", spsa.iteration, score);
+        print_parameters(&spsa);
+    }
+}
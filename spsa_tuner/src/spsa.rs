@@ -0,0 +1,121 @@
+use crate::experiment::Parameter;
+
+use std::fs;
+use std::path::Path;
+
+/// Exponents of the gain sequences recommended by Spall
+const ALPHA: f32 = 0.602;
+const GAMMA: f32 = 0.101;
+
+/// Settings of the gain sequences, all in the normalized [0, 1] space of every parameter
+#[derive(Clone, Copy, Debug)]
+pub struct SpsaSettings {
+    /// size of the first update for a gradient estimate of 1
+    pub a: f32,
+    /// size of the first perturbation
+    pub c: f32,
+    /// stability constant, delays the decay of `a`
+    pub big_a: f32,
+}
+
+/// State of a tuning run, everything needed to resume it
+pub struct Spsa {
+    pub parameters: Vec<Parameter>,
+    /// current estimate of every parameter, normalized
+    pub theta: Vec<f32>,
+    pub iteration: usize,
+    pub settings: SpsaSettings,
+}
+
+impl Spsa {
+    pub fn new(parameters: Vec<Parameter>, settings: SpsaSettings) -> Spsa {
+        let theta = vec![0.5; parameters.len()];
+        return Spsa {
+            parameters,
+            theta,
+            iteration: 0,
+            settings,
+        };
+    }
+
+    /// Perturbation size of the current iteration
+    pub fn c_k(&self) -> f32 {
+        return self.settings.c / ((self.iteration + 1) as f32).powf(GAMMA);
+    }
+
+    fn a_k(&self) -> f32 {
+        let k = (self.iteration + 1) as f32 + self.settings.big_a;
+        return self.settings.a / k.powf(ALPHA);
+    }
+
+    /// `theta` moved by `c_k` in the direction of `delta`, clamped to the parameter ranges
+    pub fn perturbed(&self, delta: &[f32], sign: f32) -> Vec<f32> {
+        let c_k = self.c_k();
+        return self
+            .theta
+            .iter()
+            .zip(delta.iter())
+            .map(|(theta, delta)| (theta + sign * c_k * delta).clamp(0., 1.))
+            .collect();
+    }
+
+    /// Moves `theta` along the gradient estimate, `score` being the score of the plus
+    /// perturbation against the minus one, from -1 to 1
+    pub fn update(&mut self, delta: &[f32], score: f32) {
+        let a_k = self.a_k();
+        let c_k = self.c_k();
+        for (theta, delta) in self.theta.iter_mut().zip(delta.iter()) {
+            let gradient = score / (2. * c_k * delta);
+            *theta = (*theta + a_k * gradient).clamp(0., 1.);
+        }
+        self.iteration += 1;
+    }
+
+    /// `set_var_from_string` arguments for the normalized values `x`
+    pub fn assignments(&self, x: &[f32]) -> Vec<(String, String)> {
+        return self
+            .parameters
+            .iter()
+            .zip(x.iter())
+            .map(|(parameter, x)| (parameter.name.clone(), parameter.format(*x)))
+            .collect();
+    }
+
+    /// Writes the iteration and the current values, one `name value` pair per line
+    pub fn save(&self, path: &str) {
+        let mut content = format!("iteration {}\n", self.iteration);
+        for (parameter, theta) in self.parameters.iter().zip(self.theta.iter()) {
+            content += &format!("{} {}\n", parameter.name, parameter.unrounded(*theta));
+        }
+        // write to a temporary file first, so an interrupted run never leaves half a checkpoint
+        let temporary = format!("{}.tmp", path);
+        fs::write(&temporary, content).expect("Unable to write checkpoint");
+        fs::rename(&temporary, path).expect("Unable to write checkpoint");
+    }
+
+    /// Continues from the checkpoint at `path` if there is one. Parameters of the
+    /// experiment missing in the checkpoint start in the middle of their range
+    pub fn resume(&mut self, path: &str) -> bool {
+        if !Path::new(path).exists() {
+            return false;
+        }
+        let content = fs::read_to_string(path).expect("Unable to read checkpoint");
+        for line in content.lines() {
+            let words: Vec<&str> = line.split_whitespace().collect();
+            if words.len() != 2 {
+                continue;
+            }
+            if words[0] == "iteration" {
+                self.iteration = words[1].parse().expect("Got wrong iteration");
+                continue;
+            }
+            let value: f32 = words[1].parse().expect("Got wrong val");
+            for (parameter, theta) in self.parameters.iter().zip(self.theta.iter_mut()) {
+                if parameter.name == words[0] {
+                    *theta = parameter.normalize(value).clamp(0., 1.);
+                }
+            }
+        }
+        return true;
+    }
+}
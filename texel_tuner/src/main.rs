@@ -0,0 +1,246 @@
+use argparse::{ArgumentParser, Store};
+use game_sdk::PlayerColor;
+use logger::WriteState;
+use logic_player::{phase_of_turn, FeatureVector, TexelWeights, DEFAULT_TEXEL};
+
+const WEIGHTS: usize = 20;
+
+/// Field names of the clop player's `MinimaxParameters`, in the order of `to_array`
+const NAMES: [&str; WEIGHTS] = [
+    "adj_distances_start",
+    "adj_distances_end",
+    "swarm_start",
+    "swarm_end",
+    "adj_center_start",
+    "adj_center_end",
+    "adj_border_start",
+    "adj_border_end",
+    "count_start",
+    "count_end",
+    "adj_mobility_start",
+    "adj_mobility_end",
+    "adj_attacked_start",
+    "adj_attacked_end",
+    "adj_defended_start",
+    "adj_defended_end",
+    "adj_joining_start",
+    "adj_joining_end",
+    "adj_blocked_distances_start",
+    "adj_blocked_distances_end",
+];
+
+/// A logged position reduced to what the loss needs. The evaluation is linear in the
+/// weights, so it is stored as one coefficient per weight
+struct Position {
+    coefficients: [f32; WEIGHTS],
+    result: f32,
+}
+
+fn to_array(weights: &TexelWeights) -> [f32; WEIGHTS] {
+    return [
+        weights.adj_distances_start,
+        weights.adj_distances_end,
+        weights.swarm_start,
+        weights.swarm_end,
+        weights.adj_center_start,
+        weights.adj_center_end,
+        weights.adj_border_start,
+        weights.adj_border_end,
+        weights.count_start,
+        weights.count_end,
+        weights.adj_mobility_start,
+        weights.adj_mobility_end,
+        weights.adj_attacked_start,
+        weights.adj_attacked_end,
+        weights.adj_defended_start,
+        weights.adj_defended_end,
+        weights.adj_joining_start,
+        weights.adj_joining_end,
+        weights.adj_blocked_distances_start,
+        weights.adj_blocked_distances_end,
+    ];
+}
+
+fn from_array(w: &[f32; WEIGHTS]) -> TexelWeights {
+    return TexelWeights {
+        adj_distances_start: w[0],
+        adj_distances_end: w[1],
+        swarm_start: w[2],
+        swarm_end: w[3],
+        adj_center_start: w[4],
+        adj_center_end: w[5],
+        adj_border_start: w[6],
+        adj_border_end: w[7],
+        count_start: w[8],
+        count_end: w[9],
+        adj_mobility_start: w[10],
+        adj_mobility_end: w[11],
+        adj_attacked_start: w[12],
+        adj_attacked_end: w[13],
+        adj_defended_start: w[14],
+        adj_defended_end: w[15],
+        adj_joining_start: w[16],
+        adj_joining_end: w[17],
+        adj_blocked_distances_start: w[18],
+        adj_blocked_distances_end: w[19],
+    };
+}
+
+fn to_position(state: &WriteState) -> Option<Position> {
+    let red = FeatureVector::from_data(&state.data, &PlayerColor::Red)?;
+    let blue = FeatureVector::from_data(&state.data, &PlayerColor::Blue)?;
+    let phase = phase_of_turn(state.turn);
+    let mut coefficients = [0.; WEIGHTS];
+    for (i, coefficient) in coefficients.iter_mut().enumerate() {
+        let mut unit = [0.; WEIGHTS];
+        unit[i] = 1.;
+        let unit = from_array(&unit);
+        *coefficient = unit.rate_features(&red, phase) - unit.rate_features(&blue, phase);
+    }
+    let result = match state.winner {
+        Some(PlayerColor::Red) => 1.,
+        Some(PlayerColor::Blue) => 0.,
+        None => 0.5,
+    };
+    return Some(Position {
+        coefficients,
+        result,
+    });
+}
+
+fn evaluate(position: &Position, weights: &[f32; WEIGHTS]) -> f32 {
+    return position
+        .coefficients
+        .iter()
+        .zip(weights.iter())
+        .map(|(c, w)| c * w)
+        .sum();
+}
+
+fn sigmoid(k: f32, x: f32) -> f32 {
+    return 1. / (1. + (-k * x).exp());
+}
+
+/// Mean squared error between the predicted winning probability and the actual result
+fn loss(positions: &[Position], weights: &[f32; WEIGHTS], k: f32) -> f32 {
+    let mut sum = 0.;
+    for position in positions {
+        let error = position.result - sigmoid(k, evaluate(position, weights));
+        sum += error * error;
+    }
+    return sum / positions.len() as f32;
+}
+
+/// Scaling of the sigmoid that fits the starting weights best, so the loss of the tuned
+/// weights is comparable to the one they started with
+fn fit_k(positions: &[Position], weights: &[f32; WEIGHTS]) -> f32 {
+    let mut best_k = 1.;
+    let mut best_loss = loss(positions, weights, best_k);
+    let mut step = 0.5;
+    while step > 1e-4 {
+        let mut improved = false;
+        for &k in [best_k - step, best_k + step].iter() {
+            if k <= 0. {
+                continue;
+            }
+            let current = loss(positions, weights, k);
+            if current < best_loss {
+                best_loss = current;
+                best_k = k;
+                improved = true;
+            }
+        }
+        if !improved {
+            step /= 2.;
+        }
+    }
+    return best_k;
+}
+
+fn gradient(positions: &[Position], weights: &[f32; WEIGHTS], k: f32) -> [f32; WEIGHTS] {
+    let mut gradient = [0.; WEIGHTS];
+    for position in positions {
+        let predicted = sigmoid(k, evaluate(position, weights));
+        let factor = -2. * (position.result - predicted) * predicted * (1. - predicted) * k;
+        for (value, coefficient) in gradient.iter_mut().zip(position.coefficients.iter()) {
+            *value += factor * coefficient;
+        }
+    }
+    for value in gradient.iter_mut() {
+        *value /= positions.len() as f32;
+    }
+    return gradient;
+}
+
+fn print_parameters(weights: &[f32; WEIGHTS]) {
+    println!("MinimaxParameters {{");
+    for (name, weight) in NAMES.iter().zip(weights.iter()) {
+        println!("    {}: {:.3},", name, weight);
+    }
+    println!("    ..MINIMAX");
+    println!("}}");
+}
+
+fn main() {
+    let mut directory = "replays/vals/".to_string();
+    let mut epochs = 10000;
+    let mut learning_rate: f32 = 10.;
+    let mut k: f32 = 0.;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.refer(&mut directory).add_option(
+            &["-d", "--directory"],
+            Store,
+            "Directory of the logged positions, defaults to replays/vals/",
+        );
+        ap.refer(&mut epochs)
+            .add_option(&["-e", "--epochs"], Store, "Number of gradient steps");
+        ap.refer(&mut learning_rate).add_option(
+            &["-l", "--learning-rate"],
+            Store,
+            "Size of a gradient step",
+        );
+        ap.refer(&mut k).add_option(
+            &["-k", "--scaling"],
+            Store,
+            "Scaling of the evaluation, fitted to the starting weights if not given",
+        );
+        ap.parse_args_or_exit();
+    }
+
+    let positions: Vec<Position> = logger::read_directory(&directory)
+        .iter()
+        .filter_map(to_position)
+        .collect();
+    if positions.is_empty() {
+        println!("No positions with evaluation data found in {}", directory);
+        return;
+    }
+    println!("Loaded {} positions", positions.len());
+
+    let mut weights = to_array(&DEFAULT_TEXEL);
+    if k <= 0. {
+        k = fit_k(&positions, &weights);
+    }
+    println!(
+        "k {:.4} | start loss {:.6}",
+        k,
+        loss(&positions, &weights, k)
+    );
+
+    for epoch in 0..epochs {
+        let gradient = gradient(&positions, &weights, k);
+        for (weight, step) in weights.iter_mut().zip(gradient.iter()) {
+            *weight -= learning_rate * step;
+        }
+        if epoch % 1000 == 0 {
+            println!(
+                "epoch {} | loss {:.6}",
+                epoch,
+                loss(&positions, &weights, k)
+            );
+        }
+    }
+    println!("end loss {:.6}", loss(&positions, &weights, k));
+    print_parameters(&weights);
+}
@@ -0,0 +1,123 @@
+mod samples;
+mod train;
+
+use argparse::{ArgumentParser, Store, StoreTrue};
+use logic_player::Network;
+use rand::seq::SliceRandom;
+use rand::thread_rng;
+use std::fs;
+
+use crate::samples::{count_winners, to_sample, Sample};
+use crate::train::{loss, new_network, train_sample};
+
+/// Mean value and policy loss over `samples`
+fn mean_loss(network: &Network, samples: &[Sample]) -> (f32, f32) {
+    let mut value = 0.;
+    let mut policy = 0.;
+    for sample in samples {
+        let (v, p) = loss(network, sample);
+        value += v;
+        policy += p;
+    }
+    let n = samples.len() as f32;
+    return (value / n, policy / n);
+}
+
+fn main() {
+    let mut directory = "replays/vals/".to_string();
+    let mut output = "network.txt".to_string();
+    let mut init = String::new();
+    let mut hidden = 64;
+    let mut policy = false;
+    let mut epochs = 20;
+    let mut learning_rate: f32 = 1e-3;
+    let mut scale: f32 = 40.;
+    let mut validation: f32 = 0.1;
+    {
+        let mut ap = ArgumentParser::new();
+        ap.refer(&mut directory).add_option(
+            &["-d", "--directory"],
+            Store,
+            "Directory of the logged positions, defaults to replays/vals/",
+        );
+        ap.refer(&mut output).add_option(
+            &["-o", "--output"],
+            Store,
+            "Weights file to write, defaults to network.txt",
+        );
+        ap.refer(&mut init).add_option(
+            &["-i", "--init"],
+            Store,
+            "Weights file to continue training from instead of a new network",
+        );
+        ap.refer(&mut hidden)
+            .add_option(&["--hidden"], Store, "Size of the hidden layer");
+        ap.refer(&mut policy).add_option(
+            &["-p", "--policy"],
+            StoreTrue,
+            "Train a policy head on the logged move values",
+        );
+        ap.refer(&mut epochs)
+            .add_option(&["-e", "--epochs"], Store, "Passes over the data");
+        ap.refer(&mut learning_rate).add_option(
+            &["-l", "--learning-rate"],
+            Store,
+            "Step size of the gradient descent",
+        );
+        ap.refer(&mut scale).add_option(
+            &["-s", "--scale"],
+            Store,
+            "Value a certain win is evaluated with",
+        );
+        ap.refer(&mut validation).add_option(
+            &["--validation"],
+            Store,
+            "Share of the positions held out to measure the loss",
+        );
+        ap.parse_args_or_exit();
+    }
+
+    let states = logger::read_directory(&directory);
+    let (red, blue, draws) = count_winners(&states);
+    println!(
+        "Loaded {} positions, red won {}, blue won {}, {} drawn",
+        states.len(),
+        red,
+        blue,
+        draws
+    );
+    let mut samples: Vec<Sample> = states.iter().map(to_sample).collect();
+    if samples.len() < 2 {
+        println!("Not enough positions in {}", directory);
+        return;
+    }
+    let mut rng = thread_rng();
+    samples.shuffle(&mut rng);
+    let held_out = usize::max(1, (samples.len() as f32 * validation) as usize);
+    let test = samples.split_off(samples.len() - held_out);
+
+    let mut network = if init.is_empty() {
+        new_network(&[hidden], policy, scale)
+    } else {
+        Network::from_file(&init).expect("Unable to load network")
+    };
+    let (value, policy_loss) = mean_loss(&network, &test);
+    println!("start | value {:.4} policy {:.4}", value, policy_loss);
+
+    for epoch in 0..epochs {
+        samples.shuffle(&mut rng);
+        for sample in samples.iter() {
+            train_sample(&mut network, sample, learning_rate);
+        }
+        let (value, policy_loss) = mean_loss(&network, &test);
+        println!(
+            "epoch {} | value {:.4} policy {:.4}",
+            epoch + 1,
+            value,
+            policy_loss
+        );
+    }
+
+    fs::write(&output, network.to_text()).expect("Unable to write network");
+    println!("Wrote {}", output);
+}
@@ -0,0 +1,69 @@
+use game_sdk::{Board, GameState, PlayerColor};
+use logger::WriteState;
+use logic_player::encode;
+
+/// One training position, everything from the side to move's point of view
+pub struct Sample {
+    pub input: Vec<f32>,
+    /// 1 for a win, -1 for a loss and 0 for a draw
+    pub value: f32,
+    /// origin, destination and target probability of every legal move, empty if the
+    /// position was logged without move values
+    pub policy: Vec<(usize, usize, f32)>,
+}
+
+fn field(x: u8, y: u8) -> usize {
+    return x as usize + y as usize * 10;
+}
+
+pub fn to_sample(state: &WriteState) -> Sample {
+    let game = GameState::new(Board::new(state.board), state.turn);
+    let color = game.get_current_player_color();
+    let value = match state.winner {
+        Some(winner) if winner == color => 1.,
+        Some(_) => -1.,
+        None => 0.,
+    };
+
+    let total: f32 = state
+        .moves
+        .iter()
+        .map(|pair| f32::max(0., pair.value))
+        .sum();
+    let mut policy = Vec::new();
+    if total > 0. {
+        for action in game.get_move_list() {
+            let target = state
+                .moves
+                .iter()
+                .find(|pair| pair.action == action)
+                .map_or(0., |pair| f32::max(0., pair.value) / total);
+            policy.push((
+                field(action.x, action.y),
+                field(action.dest_x, action.dest_y),
+                target,
+            ));
+        }
+    }
+
+    return Sample {
+        input: encode(&game),
+        value,
+        policy,
+    };
+}
+
+/// Positions won by red, won by blue and drawn
+pub fn count_winners(states: &[WriteState]) -> (usize, usize, usize) {
+    let mut red = 0;
+    let mut blue = 0;
+    let mut draws = 0;
+    for state in states {
+        match state.winner {
+            Some(PlayerColor::Red) => red += 1,
+            Some(PlayerColor::Blue) => blue += 1,
+            None => draws += 1,
+        }
+    }
+    return (red, blue, draws);
+}
@@ -0,0 +1,125 @@
+use logic_player::{Activation, Layer, Network, INPUTS, POLICY_OUTPUTS};
+use rand::{thread_rng, Rng};
+
+use crate::samples::Sample;
+
+const FIELDS: usize = 100;
+
+/// A network with one hidden layer per entry of `hidden`, initialized uniformly with the
+/// Glorot bounds
+pub fn new_network(hidden: &[usize], policy: bool, scale: f32) -> Network {
+    let mut rng = thread_rng();
+    let mut layers = Vec::new();
+    let mut inputs = INPUTS;
+    let outputs = if policy { POLICY_OUTPUTS } else { 1 };
+    let sizes: Vec<(usize, Activation)> = hidden
+        .iter()
+        .map(|&size| (size, Activation::Relu))
+        .chain(Some((outputs, Activation::Linear)))
+        .collect();
+    for (size, activation) in sizes {
+        let bound = (6. / (inputs + size) as f32).sqrt();
+        layers.push(Layer {
+            inputs,
+            outputs: size,
+            weights: (0..inputs * size)
+                .map(|_| rng.gen_range(-bound, bound))
+                .collect(),
+            biases: vec![0.; size],
+            activation,
+        });
+        inputs = size;
+    }
+    return Network { scale, layers };
+}
+
+fn derivative(activation: Activation, output: f32) -> f32 {
+    return match activation {
+        Activation::Linear => 1.,
+        Activation::Relu => {
+            if output > 0. {
+                1.
+            } else {
+                0.
+            }
+        }
+        Activation::Tanh => 1. - output * output,
+    };
+}
+
+/// Value and policy loss of `network` on `sample`
+pub fn loss(network: &Network, sample: &Sample) -> (f32, f32) {
+    let output = network.forward(&sample.input);
+    let value = output[0].tanh();
+    let value_loss = (value - sample.value) * (value - sample.value);
+    let policy_loss = match policy_probabilities(&output, sample) {
+        Some(probabilities) => sample
+            .policy
+            .iter()
+            .zip(probabilities.iter())
+            .map(|(&(_, _, target), p)| -target * f32::max(*p, 1e-9).ln())
+            .sum(),
+        None => 0.,
+    };
+    return (value_loss, policy_loss);
+}
+
+/// Softmax over the legal moves of `sample`, `None` without a policy head or target
+fn policy_probabilities(output: &[f32], sample: &Sample) -> Option<Vec<f32>> {
+    if output.len() != POLICY_OUTPUTS || sample.policy.is_empty() {
+        return None;
+    }
+    let logits: Vec<f32> = sample
+        .policy
+        .iter()
+        .map(|&(from, to, _)| output[1 + from] + output[1 + FIELDS + to])
+        .collect();
+    let max = logits.iter().cloned().fold(f32::MIN, f32::max);
+    let exponentials: Vec<f32> = logits.iter().map(|logit| (logit - max).exp()).collect();
+    let sum: f32 = exponentials.iter().sum();
+    return Some(exponentials.iter().map(|e| e / sum).collect());
+}
+
+/// One step of stochastic gradient descent on `sample`
+pub fn train_sample(network: &mut Network, sample: &Sample, learning_rate: f32) {
+    // forward pass, remembering every layer's input
+    let mut activations = vec![sample.input.clone()];
+    for layer in network.layers.iter() {
+        let next = layer.forward(&activations[activations.len() - 1]);
+        activations.push(next);
+    }
+    let output = &activations[activations.len() - 1];
+
+    let mut gradient = vec![0.; output.len()];
+    let value = output[0].tanh();
+    gradient[0] = 2. * (value - sample.value) * (1. - value * value);
+    if let Some(probabilities) = policy_probabilities(output, sample) {
+        for (&(from, to, target), p) in sample.policy.iter().zip(probabilities.iter()) {
+            gradient[1 + from] += p - target;
+            gradient[1 + FIELDS + to] += p - target;
+        }
+    }
+
+    for (index, layer) in network.layers.iter_mut().enumerate().rev() {
+        let input = &activations[index];
+        let output = &activations[index + 1];
+        let mut input_gradient = vec![0.; layer.inputs];
+        for o in 0..layer.outputs {
+            let delta = gradient[o] * derivative(layer.activation, output[o]);
+            if delta == 0. {
+                continue;
+            }
+            let row = &mut layer.weights[o * layer.inputs..(o + 1) * layer.inputs];
+            for ((weight, x), input_gradient) in row
+                .iter_mut()
+                .zip(input.iter())
+                .zip(input_gradient.iter_mut())
+            {
+                *input_gradient += *weight * delta;
+                *weight -= learning_rate * delta * x;
+            }
+            layer.biases[o] -= learning_rate * delta;
+        }
+        gradient = input_gradient;
+    }
+}
@@ -6,15 +6,26 @@ extern crate time;
 mod xml_client;
 mod xml_utils;
 
-use argparse::{ArgumentParser, Store};
+use argparse::{ArgumentParser, Store, StoreFalse, StoreOption};
 use xml_client::XMLClient;
 
+use logic_player::{OpeningBook, SearchLimit, DEFAULT_NODE_BUDGET};
 use logic_player::RavePlayer as Player;
 
+use std::sync::Arc;
+
 fn main() {
 	let mut host = "localhost".to_string();
 	let mut port = "13050".to_string();
 	let mut reservation = "".to_string();
+	let mut book = "".to_string();
+	let mut book_randomness: f32 = 0.;
+	let mut nodes = DEFAULT_NODE_BUDGET;
+	let mut ponder = true;
+	let mut export = "".to_string();
+	let mut export_nodes: usize = 200;
+	let mut limit = "".to_string();
+	let mut seed: Option<u64> = None;
 	{
         let mut ap = ArgumentParser::new();
         ap.refer(&mut host).add_option(
@@ -31,14 +42,80 @@ fn main() {
             &["-r", "--reservation"],
             Store,
             "Reservation to join",
+        );
+		ap.refer(&mut book).add_option(
+            &["-b", "--book"],
+            Store,
+            "Opening book to play from",
+        );
+		ap.refer(&mut book_randomness).add_option(
+            &["--book-randomness"],
+            Store,
+            "Temperature of the book move choice, 0 always plays the best move",
+        );
+		ap.refer(&mut nodes).add_option(
+            &["-n", "--nodes"],
+            Store,
+            "Most nodes the search tree may hold",
+        );
+		ap.refer(&mut ponder).add_option(
+            &["--no-ponder"],
+            StoreFalse,
+            "Do not search while the opponent is thinking",
+        );
+		ap.refer(&mut export).add_option(
+            &["--export-tree"],
+            Store,
+            "Directory to write the search tree of every move to, as DOT and JSON",
+        );
+		ap.refer(&mut export_nodes).add_option(
+            &["--export-nodes"],
+            Store,
+            "Most visited nodes of the tree to export",
+        );
+		ap.refer(&mut limit).add_option(
+            &["--limit"],
+            Store,
+            "Search limit of every move, e.g. millis=100 or nodes=20000",
+        );
+		ap.refer(&mut seed).add_option(
+            &["--seed"],
+            StoreOption,
+            "Seed of the random choices, with a node limit every run plays the same moves",
         );
         ap.parse_args_or_exit();
     }
 	println!("Parameters got are: {}:{} w/ reservation {}", host, port, reservation);
+	let limit = if limit.is_empty() {
+		None
+	} else {
+		Some(SearchLimit::from_string(&limit).expect("Invalid search limit"))
+	};
+	if let Some(SearchLimit::Nodes(_)) = limit {
+		// what the player searches on the opponent's time depends on how long it takes
+		ponder = false;
+	}
     let mut client = XMLClient::new();
+	client.set_pondering(ponder);
 
 	// Insert custom client listener here:
-	client.add_listener(Box::new(Player::new(None,-1)));
+	let mut player = Player::new(None,-1);
+	player.set_node_budget(nodes);
+	if let Some(limit) = limit {
+		player.set_limit(limit);
+	}
+	if let Some(seed) = seed {
+		player.set_seed(seed);
+	}
+	if !export.is_empty() {
+		player.set_tree_export(export, export_nodes);
+	}
+	if !book.is_empty() {
+		let book = OpeningBook::from_file(&book).expect("Unable to load opening book");
+		println!("Loaded {} book positions", book.len());
+		player.set_book(Arc::new(book), book_randomness);
+	}
+	client.add_listener(Box::new(player));
 	
     client.run(&(host + ":" + port.as_str()), &reservation);
 }
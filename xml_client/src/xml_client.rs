@@ -6,12 +6,15 @@ use self::xml::reader::*;
 use super::xml_utils::XMLNode;
 use std::io::{prelude::Write, BufReader, BufWriter};
 use std::net::TcpStream;
+use std::sync::mpsc::{self, Receiver, TryRecvError};
+use std::thread;
 
 pub struct XMLClient {
 	listeners: Vec<Box<dyn ClientListener>>,
 	my_color: Option<String>,
 	game_state: Option<GameState>,
 	room: Option<Room>,
+	pondering: bool,
 }
 
 impl XMLClient {
@@ -21,9 +24,15 @@ impl XMLClient {
 			my_color: None,
 			game_state: None,
 			room: None,
+			pondering: true,
 		};
 	}
 
+	/// Whether the listeners get `on_idle` calls while the client waits for the server
+	pub fn set_pondering(&mut self, pondering: bool) {
+		self.pondering = pondering;
+	}
+
 	pub fn add_listener(&mut self, listener: Box<dyn ClientListener>) {
 		self.listeners.push(listener);
 	}
@@ -59,11 +68,50 @@ impl XMLClient {
 		}
 	}
 
+	/// Parses the messages of the server in a thread of its own, so the listeners can think
+	/// while there is none
+	fn read_in_background(stream: &TcpStream) -> Receiver<XMLNode> {
+		let stream = stream.try_clone().expect("Could not clone the connection");
+		let (tx, rx) = mpsc::channel();
+		thread::spawn(move || {
+			let mut parser = EventReader::new(BufReader::new(stream));
+			while let Some(node) = XMLNode::read_from(&mut parser) {
+				if tx.send(node).is_err() {
+					break;
+				}
+			}
+		});
+		return rx;
+	}
+
+	/// Waits for the next message of the server, the listeners ponder meanwhile once the game
+	/// started. `None` if the connection was lost
+	fn next_node(&mut self, nodes: &Receiver<XMLNode>) -> Option<XMLNode> {
+		while self.pondering
+			&& self.game_state.is_some()
+			&& self.listeners.iter().any(|listener| listener.ponders())
+		{
+			match nodes.try_recv() {
+				Ok(node) => return Some(node),
+				Err(TryRecvError::Disconnected) => return None,
+				Err(TryRecvError::Empty) => {}
+			}
+			self.fire_listeners(&mut |listener| listener.on_idle());
+		}
+		return nodes.recv().ok();
+	}
+
 	fn handle_stream(mut self, stream: &TcpStream) {
-		let mut parser = EventReader::new(BufReader::new(stream));
+		let nodes = XMLClient::read_in_background(stream);
 
 		loop {
-			let mut node = XMLNode::read_from(&mut parser);
+			let mut node = match self.next_node(&nodes) {
+				Some(node) => node,
+				None => {
+					println!("Connection lost");
+					break;
+				}
+			};
 			/*println!(
 				"{} {:?} {:?}",
 				node.get_name().as_str(),
@@ -5,8 +5,7 @@ use self::game_sdk::*;
 use self::xml::reader::{EventReader, XmlEvent};
 use std::collections::HashMap;
 use std::collections::VecDeque;
-use std::io::BufReader;
-use std::net::TcpStream;
+use std::io::Read;
 use std::vec::Vec;
 
 #[derive(Debug)]
@@ -27,7 +26,8 @@ impl XMLNode {
 		};
 	}
 
-	pub fn read_from(xml_parser: &mut EventReader<BufReader<&TcpStream>>) -> XMLNode {
+	/// Next complete element, `None` once the stream ends or breaks
+	pub fn read_from<R: Read>(xml_parser: &mut EventReader<R>) -> Option<XMLNode> {
 		let mut node_stack: VecDeque<XMLNode> = VecDeque::new();
 		let mut has_received_first = false;
 		let mut final_node: Option<XMLNode> = None;
@@ -67,7 +67,7 @@ impl XMLNode {
 				Ok(XmlEvent::Characters(content)) => {
 					node_stack.back_mut().expect("Unexpectedly found empty XML node stack while trying to add characters").data += content.as_str();
 				}
-				Err(_) => {
+				Ok(XmlEvent::EndDocument) | Err(_) => {
 					break;
 				}
 				_ => {}
@@ -79,7 +79,7 @@ impl XMLNode {
 			}
 		}
 
-		return final_node.unwrap(); // Is guaranteed to be present due to the condition above
+		return final_node;
 	}
 
 	pub fn as_game_state(&self) -> GameState {